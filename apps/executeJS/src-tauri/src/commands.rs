@@ -1,6 +1,11 @@
-use crate::js_executor::{execute_javascript_code, JsExecutionResult};
+use crate::js_executor::{
+    channel_sink, execute_javascript_code, JsExecutionResult, ReplSessions, RunningExecutions,
+};
+use deno_runtime::{ConsoleEntry, ExecutionMode, JsxRuntime, Language, SessionId};
 use serde::{Deserialize, Serialize};
 use std::process::Command;
+use tauri::ipc::Channel;
+use tauri::State;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AppInfo {
@@ -62,8 +67,26 @@ struct OxlintSpan {
 }
 
 #[tauri::command]
-pub async fn execute_js(code: &str) -> Result<JsExecutionResult, JsExecutionResult> {
-    let result = execute_javascript_code(code).await;
+pub async fn execute_js(
+    running: State<'_, RunningExecutions>,
+    code: &str,
+    language: Option<Language>,
+    mode: Option<ExecutionMode>,
+    jsx: Option<JsxRuntime>,
+    run_id: Option<String>,
+    on_output: Option<Channel<ConsoleEntry>>,
+) -> Result<JsExecutionResult, JsExecutionResult> {
+    let output_sink = on_output.map(channel_sink);
+    let result = execute_javascript_code(
+        code,
+        language.unwrap_or_default(),
+        mode.unwrap_or_default(),
+        jsx.unwrap_or_default(),
+        run_id.as_deref(),
+        &running,
+        output_sink,
+    )
+    .await;
 
     if result.success {
         Ok(result)
@@ -78,10 +101,68 @@ pub async fn execute_js(code: &str) -> Result<JsExecutionResult, JsExecutionResu
             timestamp: chrono::Utc::now(),
             success: false,
             error: Some(error_message.clone()),
+            error_kind: result.error_kind,
+            exception: result.exception,
+            value: None,
+            entries: result.entries,
         })
     }
 }
 
+// 실행 중인 스크립트 취소 (run_id는 execute_js 호출 시 넘긴 값)
+#[tauri::command]
+pub fn cancel_execution(running: State<'_, RunningExecutions>, run_id: String) -> bool {
+    running.cancel(&run_id)
+}
+
+// REPL 세션 생성 (이후 실행은 같은 전역 스코프를 공유)
+#[tauri::command]
+pub async fn create_session(sessions: State<'_, ReplSessions>) -> Result<SessionId, String> {
+    sessions.create().await
+}
+
+#[tauri::command]
+pub async fn execute_in_session(
+    sessions: State<'_, ReplSessions>,
+    session_id: SessionId,
+    code: &str,
+    on_output: Option<Channel<ConsoleEntry>>,
+) -> Result<JsExecutionResult, JsExecutionResult> {
+    let result = sessions
+        .execute(session_id, code, on_output.map(channel_sink))
+        .await;
+
+    if result.success {
+        Ok(result)
+    } else {
+        Err(result)
+    }
+}
+
+#[tauri::command]
+pub async fn reset_session(
+    sessions: State<'_, ReplSessions>,
+    session_id: SessionId,
+) -> Result<(), String> {
+    sessions.reset(session_id).await
+}
+
+#[tauri::command]
+pub async fn cancel_session_execution(
+    sessions: State<'_, ReplSessions>,
+    session_id: SessionId,
+) -> Result<(), String> {
+    sessions.cancel(session_id).await
+}
+
+#[tauri::command]
+pub async fn dispose_session(
+    sessions: State<'_, ReplSessions>,
+    session_id: SessionId,
+) -> Result<(), String> {
+    sessions.dispose(session_id).await
+}
+
 #[tauri::command]
 pub fn get_app_info() -> AppInfo {
     AppInfo {
@@ -1,5 +1,44 @@
-use deno_runtime::DenoExecutor;
+use deno_runtime::{
+    CancelHandle, ConsoleEntry, DenoExecutor, ExecutionError, ExecutionMode, ExecutionResult,
+    ExecutorOptions, JsValuePreview, JsxRuntime, Language, OutputSink, PartialOutput, ScriptError,
+    SessionId,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::ipc::Channel;
+use tokio::sync::Mutex;
+
+/// 스크립트 한 번 실행에 허용하는 최대 시간
+const EXECUTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 스크립트가 사용할 수 있는 최대 V8 힙 크기
+const MAX_HEAP_SIZE: usize = 512 * 1024 * 1024;
+
+/// 프론트엔드에서 구분해서 보여줄 실행 실패 종류
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JsErrorKind {
+    /// 스크립트 자체 오류 (문법 오류, 예외 등)
+    Runtime,
+    /// 제한 시간 초과
+    Timeout,
+    /// 사용자가 실행 취소
+    Cancelled,
+    /// 힙 한도 초과
+    OutOfMemory,
+}
+
+impl JsErrorKind {
+    fn from_error(error: &anyhow::Error) -> Self {
+        match error.downcast_ref::<ExecutionError>() {
+            Some(ExecutionError::TimedOut(_)) => JsErrorKind::Timeout,
+            Some(ExecutionError::Cancelled) => JsErrorKind::Cancelled,
+            Some(ExecutionError::OutOfMemory(_)) => JsErrorKind::OutOfMemory,
+            Some(ExecutionError::Exception(_)) | None => JsErrorKind::Runtime,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsExecutionResult {
@@ -8,9 +47,98 @@ pub struct JsExecutionResult {
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub success: bool,
     pub error: Option<String>,
+    pub error_kind: Option<JsErrorKind>,
+    /// 스크립트에서 던진 예외 (에러 이름, 호출 스택, 사용자 코드 위치)
+    pub exception: Option<ScriptError>,
+    /// 마지막 표현식의 값 미리보기
+    pub value: Option<JsValuePreview>,
+    /// 호출 순서대로 정렬된 콘솔 출력 (레벨, 위치, 구조화된 인자 포함)
+    pub entries: Vec<ConsoleEntry>,
+}
+
+impl JsExecutionResult {
+    fn from_output(code: &str, result: anyhow::Result<ExecutionResult>) -> Self {
+        let timestamp = chrono::Utc::now();
+        match result {
+            Ok(ExecutionResult { output, value }) => {
+                // 콘솔 출력이 없으면 마지막 표현식의 값을 대신 표시
+                let result = match &value {
+                    _ if !output.is_empty() => output.to_text(),
+                    Some(value) if *value != JsValuePreview::Undefined => value.to_string(),
+                    _ => "코드가 실행되었습니다.".to_string(),
+                };
+
+                JsExecutionResult {
+                    code: code.to_string(),
+                    result,
+                    timestamp,
+                    success: true,
+                    error: None,
+                    error_kind: None,
+                    exception: None,
+                    value,
+                    entries: output.entries,
+                }
+            }
+            Err(error) => JsExecutionResult {
+                code: code.to_string(),
+                result: String::new(),
+                timestamp,
+                success: false,
+                error: Some(format!("{}", error)),
+                error_kind: Some(JsErrorKind::from_error(&error)),
+                exception: match error.downcast_ref::<ExecutionError>() {
+                    Some(ExecutionError::Exception(exception)) => Some(exception.clone()),
+                    _ => None,
+                },
+                value: None,
+                // 실패하기 전까지 출력된 콘솔 출력
+                entries: error
+                    .downcast_ref::<PartialOutput>()
+                    .map(|partial| partial.output.entries.clone())
+                    .unwrap_or_default(),
+            },
+        }
+    }
 }
 
-pub async fn execute_javascript_code(code: &str) -> JsExecutionResult {
+/// 실행 중인 스크립트의 취소 핸들 (프론트엔드가 넘긴 run_id 기준)
+#[derive(Default)]
+pub struct RunningExecutions {
+    handles: std::sync::Mutex<HashMap<String, CancelHandle>>,
+}
+
+impl RunningExecutions {
+    /// 실행 취소 (해당 run_id가 실행 중이 아니면 false)
+    pub fn cancel(&self, run_id: &str) -> bool {
+        match self.handles.lock().unwrap().get(run_id) {
+            Some(handle) => {
+                handle.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// 콘솔 출력을 실행 도중에 프론트엔드 채널로 전달하는 sink
+pub fn channel_sink(channel: Channel<ConsoleEntry>) -> OutputSink {
+    OutputSink::new(move |entry| {
+        if let Err(e) = channel.send(entry) {
+            eprintln!("[OutputSink] 출력 전달 실패: {}", e);
+        }
+    })
+}
+
+pub async fn execute_javascript_code(
+    code: &str,
+    language: Language,
+    mode: ExecutionMode,
+    jsx: JsxRuntime,
+    run_id: Option<&str>,
+    running: &RunningExecutions,
+    output_sink: Option<OutputSink>,
+) -> JsExecutionResult {
     let timestamp = chrono::Utc::now();
 
     // 빈 코드 체크
@@ -21,38 +149,150 @@ pub async fn execute_javascript_code(code: &str) -> JsExecutionResult {
             timestamp,
             success: false,
             error: Some("코드가 비어있습니다".to_string()),
+            error_kind: None,
+            exception: None,
+            value: None,
+            entries: Vec::new(),
         };
     }
 
     // DenoExecutor를 사용한 실제 JavaScript 실행
-    match execute_with_deno(code).await {
-        Ok(output) => JsExecutionResult {
-            code: code.to_string(),
-            result: output,
-            timestamp,
-            success: true,
-            error: None,
-        },
-        Err(error) => JsExecutionResult {
-            code: code.to_string(),
-            result: String::new(),
-            timestamp,
-            success: false,
-            error: Some(format!("{}", error)),
-        },
+    JsExecutionResult::from_output(
+        code,
+        execute_with_deno(code, language, mode, jsx, run_id, running, output_sink).await,
+    )
+}
+
+/// 앱에서 사용하는 실행 제한 (시간, 메모리)
+fn executor_options() -> ExecutorOptions {
+    ExecutorOptions {
+        timeout: Some(EXECUTION_TIMEOUT),
+        max_heap_size: Some(MAX_HEAP_SIZE),
+        output_sink: None,
+        jsx: JsxRuntime::default(),
+        mode: ExecutionMode::Auto,
+        npm_conditions: None,
+        npm_offline: false,
     }
 }
 
 /// Deno를 사용한 JavaScript 코드 실행
-async fn execute_with_deno(code: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+async fn execute_with_deno(
+    code: &str,
+    language: Language,
+    mode: ExecutionMode,
+    jsx: JsxRuntime,
+    run_id: Option<&str>,
+    running: &RunningExecutions,
+    output_sink: Option<OutputSink>,
+) -> anyhow::Result<ExecutionResult> {
     // DenoExecutor 생성
-    let mut executor = DenoExecutor::new().await.map_err(|e| format!("{}", e))?;
+    let mut executor = DenoExecutor::with_options(ExecutorOptions {
+        output_sink,
+        mode,
+        jsx,
+        ..executor_options()
+    })
+    .await?;
+
+    // 실행 중에 취소할 수 있도록 핸들 등록
+    if let Some(run_id) = run_id {
+        running
+            .handles
+            .lock()
+            .unwrap()
+            .insert(run_id.to_string(), executor.cancel_handle());
+    }
 
     // 코드 실행
-    let result = executor
-        .execute_script("index.js", code)
-        .await
-        .map_err(|e| format!("{}", e))?;
+    let result = executor.execute_script_with_language(code, language).await;
+
+    if let Some(run_id) = run_id {
+        running.handles.lock().unwrap().remove(run_id);
+    }
+
+    result
+}
 
-    Ok(result)
+/// REPL 세션을 보관하는 앱 전역 상태
+#[derive(Default)]
+pub struct ReplSessions {
+    executor: Mutex<Option<DenoExecutor>>,
+}
+
+impl ReplSessions {
+    /// 세션 생성 (세션용 DenoExecutor는 처음 필요할 때 생성)
+    pub async fn create(&self) -> Result<SessionId, String> {
+        let mut guard = self.executor.lock().await;
+        if guard.is_none() {
+            let executor = DenoExecutor::with_options(executor_options())
+                .await
+                .map_err(|e| format!("{}", e))?;
+            *guard = Some(executor);
+        }
+        let executor = guard.as_mut().expect("executor initialized above");
+        executor
+            .create_session()
+            .await
+            .map_err(|e| format!("{}", e))
+    }
+
+    /// 세션에서 코드 실행 (출력은 `output_sink`로 실시간 전달)
+    pub async fn execute(
+        &self,
+        session_id: SessionId,
+        code: &str,
+        output_sink: Option<OutputSink>,
+    ) -> JsExecutionResult {
+        // 실행이 끝날 때까지 락을 잡지 않도록 세션 핸들만 복제
+        let session = self
+            .executor
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|executor| executor.session(session_id));
+
+        let result = match session {
+            Some(session) => session.execute_with_sink(code, output_sink).await,
+            None => Err(anyhow::anyhow!("세션을 찾을 수 없습니다: {}", session_id)),
+        };
+
+        JsExecutionResult::from_output(code, result)
+    }
+
+    /// 세션에서 실행 중인 코드 취소
+    pub async fn cancel(&self, session_id: SessionId) -> Result<(), String> {
+        let session = self
+            .executor
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|executor| executor.session(session_id))
+            .ok_or_else(|| format!("세션을 찾을 수 없습니다: {}", session_id))?;
+        session.cancel_handle().cancel();
+        Ok(())
+    }
+
+    /// 세션 전역 상태 초기화
+    pub async fn reset(&self, session_id: SessionId) -> Result<(), String> {
+        let session = self
+            .executor
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|executor| executor.session(session_id))
+            .ok_or_else(|| format!("세션을 찾을 수 없습니다: {}", session_id))?;
+        session.reset().await.map_err(|e| format!("{}", e))
+    }
+
+    /// 세션 종료
+    pub async fn dispose(&self, session_id: SessionId) -> Result<(), String> {
+        self.executor
+            .lock()
+            .await
+            .as_mut()
+            .ok_or_else(|| format!("세션을 찾을 수 없습니다: {}", session_id))?
+            .dispose_session(session_id)
+            .map_err(|e| format!("{}", e))
+    }
 }
@@ -7,6 +7,7 @@ mod commands;
 mod js_executor;
 
 use commands::*;
+use js_executor::{ReplSessions, RunningExecutions};
 use tauri::menu::{MenuBuilder, SubmenuBuilder};
 use tauri::{Manager, WebviewUrl, WebviewWindowBuilder};
 
@@ -19,7 +20,9 @@ pub fn run() {
             .plugin(tauri_plugin_opener::init())
             .plugin(tauri_plugin_fs::init())
             .plugin(tauri_plugin_clipboard_manager::init())
-            .plugin(tauri_plugin_store::Builder::default().build());
+            .plugin(tauri_plugin_store::Builder::default().build())
+            .manage(ReplSessions::default())
+            .manage(RunningExecutions::default());
 
         // DevTools 플러그인 추가 (개발 빌드에서만)
         #[cfg(debug_assertions)]
@@ -169,6 +172,12 @@ pub fn run() {
             })
             .invoke_handler(tauri::generate_handler![
                 execute_js,
+                cancel_execution,
+                create_session,
+                execute_in_session,
+                reset_session,
+                cancel_session_execution,
+                dispose_session,
                 get_app_info,
                 lint_code
             ])
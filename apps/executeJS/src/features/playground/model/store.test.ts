@@ -5,6 +5,9 @@ import type { JsExecutionResult } from '@/shared';
 // Tauri API 모킹
 vi.mock('@tauri-apps/api/core', () => ({
   invoke: vi.fn(),
+  Channel: class<T> {
+    onmessage: (message: T) => void = () => {};
+  },
 }));
 
 // localStorage 모킹
@@ -340,6 +343,47 @@ describe('playground store', () => {
       await executePromise;
     });
 
+    it('실행 중 채널로 받은 출력이 liveEntries에 쌓여야 함', async () => {
+      const store = usePlaygroundStore.getState();
+      const { invoke } = await import('@tauri-apps/api/core');
+
+      let finish: (value: JsExecutionResult) => void = () => {};
+      vi.mocked(invoke).mockImplementation((_command, args) => {
+        const { onOutput } = args as {
+          onOutput: { onmessage: (entry: unknown) => void };
+        };
+        onOutput.onmessage({ level: 'log', message: 'tick 1' });
+        return new Promise((resolve) => {
+          finish = resolve;
+        });
+      });
+
+      const executePromise = store.executeCode({
+        playgroundId: 'first-playground',
+        code: 'setInterval(() => console.log("tick"), 100)',
+      });
+      await vi.waitFor(() =>
+        expect(
+          usePlaygroundStore.getState().playgrounds.get('first-playground')
+            ?.liveEntries
+        ).toHaveLength(1)
+      );
+
+      finish({
+        code: 'setInterval(() => console.log("tick"), 100)',
+        result: 'tick 1',
+        timestamp: new Date().toISOString(),
+        success: true,
+      });
+      await executePromise;
+
+      const playground = usePlaygroundStore
+        .getState()
+        .playgrounds.get('first-playground');
+      expect(playground?.liveEntries).toBeUndefined();
+      expect(playground?.result?.result).toBe('tick 1');
+    });
+
     it('코드 실행 성공 시 결과가 저장되어야 함', async () => {
       const store = usePlaygroundStore.getState();
       const { invoke } = await import('@tauri-apps/api/core');
@@ -390,6 +434,69 @@ describe('playground store', () => {
       expect(playground?.isExecuting).toBe(false);
     });
 
+    it('언어를 지정하면 execute_js에 함께 전달되어야 함', async () => {
+      const store = usePlaygroundStore.getState();
+      const { invoke } = await import('@tauri-apps/api/core');
+
+      vi.mocked(invoke).mockResolvedValue({
+        code: 'const n: number = 1;',
+        result: '코드가 실행되었습니다.',
+        timestamp: new Date().toISOString(),
+        success: true,
+      });
+
+      store.setLanguage({
+        playgroundId: 'first-playground',
+        language: 'typescript',
+      });
+      await store.executeCode({
+        playgroundId: 'first-playground',
+        code: 'const n: number = 1;',
+        language: 'typescript',
+      });
+
+      const playground = usePlaygroundStore
+        .getState()
+        .playgrounds.get('first-playground');
+      expect(playground?.language).toBe('typescript');
+      expect(vi.mocked(invoke)).toHaveBeenCalledWith(
+        'execute_js',
+        expect.objectContaining({ language: 'typescript' })
+      );
+    });
+
+    it('실행 실패 시 예외 위치가 결과에 남아야 함', async () => {
+      const store = usePlaygroundStore.getState();
+      const { invoke } = await import('@tauri-apps/api/core');
+
+      const exception = {
+        name: 'TypeError',
+        message: "Cannot read properties of null (reading 'x')",
+        frames: [],
+        location: { file: '[executejs:user_code]', line: 2, column: 8 },
+        sourceLine: '  null.x;',
+        cause: null,
+      };
+
+      vi.mocked(invoke).mockRejectedValue({
+        result: '',
+        error: `Uncaught TypeError: ${exception.message}`,
+        error_kind: 'runtime',
+        exception,
+      });
+
+      await store.executeCode({
+        playgroundId: 'first-playground',
+        code: 'const a = null;\n  null.x;',
+      });
+
+      const updatedState = usePlaygroundStore.getState();
+      const playground = updatedState.playgrounds.get('first-playground');
+
+      expect(playground?.result?.error_kind).toBe('runtime');
+      expect(playground?.result?.exception).toEqual(exception);
+    });
+
     it('에러 객체에 result나 error가 없을 때 기본값이 사용되어야 함', async () => {
       const store = usePlaygroundStore.getState();
       const { invoke } = await import('@tauri-apps/api/core');
@@ -1,4 +1,4 @@
-import { JsExecutionResult } from '@/shared';
+import { ConsoleEntry, JsExecutionResult, Language } from '@/shared';
 import { create } from 'zustand';
 import { persist, createJSONStorage } from 'zustand/middleware';
 import { PLAYGROUND_STORAGE_KEY } from './const';
@@ -13,6 +13,10 @@ export interface Playground {
   id: string;
   result: JsExecutionResult | null;
   isExecuting: boolean;
+  // 실행 도중 실시간으로 받은 콘솔 출력 (실행이 끝나면 result로 대체)
+  liveEntries?: ConsoleEntry[];
+  // 코드 언어 (없으면 JavaScript)
+  language?: Language;
   // clearResult: () => void;
 }
 
@@ -25,9 +29,14 @@ interface PlaygroundState {
   closeTab: (tabId: Tab['id']) => void;
   setActiveTab: (tabId: Tab['id']) => void;
   setTabTitle: (params: { tabId: Tab['id']; title: Tab['title'] }) => void;
+  setLanguage: (params: {
+    playgroundId: Playground['id'];
+    language: Language;
+  }) => void;
   executeCode: (params: {
     playgroundId: Playground['id'];
     code: string;
+    language?: Language;
   }) => void;
 }
 
@@ -140,14 +149,32 @@ export const usePlaygroundStore = create<PlaygroundState>()(
         });
       },
 
+      // 플레이그라운드 별 코드 언어 변경
+      setLanguage: ({ playgroundId, language }) => {
+        set((state) => {
+          const playgrounds = new Map(state.playgrounds);
+          const playground = playgrounds.get(playgroundId);
+
+          if (playground) {
+            playgrounds.set(playgroundId, { ...playground, language });
+          }
+
+          return { playgrounds };
+        });
+      },
+
       // 플레이그라운드 별 코드 실행
-      executeCode: async ({ playgroundId, code }) => {
+      executeCode: async ({ playgroundId, code, language = 'javascript' }) => {
         set((state) => {
           const playgrounds = new Map(state.playgrounds);
           const playground = playgrounds.get(playgroundId);
 
           if (playground) {
-            playgrounds.set(playgroundId, { ...playground, isExecuting: true });
+            playgrounds.set(playgroundId, {
+              ...playground,
+              isExecuting: true,
+              liveEntries: [],
+            });
           }
 
           return { playgrounds };
@@ -155,9 +182,30 @@ export const usePlaygroundStore = create<PlaygroundState>()(
 
         try {
           // Tauri 백엔드의 execute_js 명령어 호출
-          const { invoke } = await import('@tauri-apps/api/core');
+          const { invoke, Channel } = await import('@tauri-apps/api/core');
+
+          // 실행이 끝나기 전에도 콘솔 출력을 바로 표시
+          const onOutput = new Channel<ConsoleEntry>();
+          onOutput.onmessage = (entry) => {
+            set((state) => {
+              const playgrounds = new Map(state.playgrounds);
+              const playground = playgrounds.get(playgroundId);
+
+              if (playground?.isExecuting) {
+                playgrounds.set(playgroundId, {
+                  ...playground,
+                  liveEntries: [...(playground.liveEntries ?? []), entry],
+                });
+              }
+
+              return { playgrounds };
+            });
+          };
+
           const result = await invoke<JsExecutionResult>('execute_js', {
             code,
+            language,
+            onOutput,
           });
 
           console.log('executeCode result -', result);
@@ -171,6 +219,7 @@ export const usePlaygroundStore = create<PlaygroundState>()(
                 ...playground,
                 result: result,
                 isExecuting: false,
+                liveEntries: undefined,
               });
             }
 
@@ -184,6 +233,8 @@ export const usePlaygroundStore = create<PlaygroundState>()(
             timestamp: new Date().toISOString(),
             success: false,
             error: error?.error ?? '알 수 없는 오류',
+            error_kind: error?.error_kind,
+            exception: error?.exception,
           };
 
           set((state) => {
@@ -195,6 +246,7 @@ export const usePlaygroundStore = create<PlaygroundState>()(
                 ...playground,
                 result: result,
                 isExecuting: false,
+                liveEntries: undefined,
               });
             }
 
@@ -1,11 +1,130 @@
 // 공통 타입 정의
 
+export type JsErrorKind = 'runtime' | 'timeout' | 'cancelled' | 'out_of_memory';
+
+// 실행할 코드의 언어 (TypeScript는 타입을 제거하고, JSX/TSX는 JSX를 함수 호출로 바꾼 뒤 실행)
+export type Language = 'javascript' | 'typescript' | 'jsx' | 'tsx';
+
+// JSX 변환 방식 (Rust의 JsxRuntime과 동일한 구조)
+export type JsxRuntime =
+  | { runtime: 'classic'; factory: string; fragmentFactory: string }
+  | { runtime: 'automatic'; importSource: string };
+
+// 마지막 표현식의 값 미리보기 (Rust의 JsValuePreview와 동일한 구조)
+export type JsValuePreview =
+  | { type: 'undefined' }
+  | { type: 'null' }
+  | { type: 'boolean'; value: boolean }
+  | { type: 'number'; description: string }
+  | { type: 'string'; value: string }
+  | { type: 'bigInt'; description: string }
+  | { type: 'symbol'; description: string }
+  | { type: 'function'; name: string; isClass: boolean }
+  | { type: 'array'; items: JsValuePreview[]; length: number }
+  | {
+      type: 'object';
+      className: string;
+      properties: { key: string; value: JsValuePreview }[];
+      more: number;
+    }
+  | {
+      type: 'map';
+      entries: { key: JsValuePreview; value: JsValuePreview }[];
+      size: number;
+    }
+  | { type: 'set'; items: JsValuePreview[]; size: number }
+  | { type: 'date'; iso: string }
+  | { type: 'regExp'; source: string }
+  | { type: 'error'; name: string; message: string; stack: string | null }
+  | {
+      type: 'promise';
+      state: 'pending' | 'fulfilled' | 'rejected';
+      value: JsValuePreview | null;
+    }
+  | { type: 'circular'; depth: number }
+  | { type: 'truncated'; className: string };
+
+export type LogLevel = 'log' | 'debug' | 'info' | 'warn' | 'error' | 'trace' | 'alert';
+
+export interface SourceLocation {
+  file: string;
+  line: number;
+  column: number;
+}
+
+// util.inspect 스타일 분류 (Rust의 SpanStyle과 동일)
+export type SpanStyle =
+  | 'special'
+  | 'number'
+  | 'bigint'
+  | 'boolean'
+  | 'undefined'
+  | 'null'
+  | 'string'
+  | 'symbol'
+  | 'date'
+  | 'regexp';
+
+// 스타일이 적용된 출력 조각 (css는 %c로 지정된 값)
+export interface StyledSpan {
+  text: string;
+  style: SpanStyle | null;
+  css: string | null;
+}
+
+export interface StackFrame {
+  functionName: string | null;
+  location: SourceLocation;
+}
+
+// console.table 데이터 (cells는 columns와 같은 순서, 값이 없는 칸은 null)
+export interface ConsoleTable {
+  indexHeader: string;
+  columns: string[];
+  rows: { index: string; cells: (JsValuePreview | null)[] }[];
+}
+
+// 콘솔 출력 종류 (표, 그룹 등은 message 대신 구조화된 값으로 그릴 수 있음)
+export type ConsoleEntryKind =
+  | { kind: 'message' }
+  | { kind: 'groupStart'; collapsed: boolean }
+  | { kind: 'groupEnd' }
+  | { kind: 'table'; table: ConsoleTable }
+  | { kind: 'trace'; stack: StackFrame[] }
+  | { kind: 'clear' };
+
+// 콘솔 출력 한 건 (Rust의 ConsoleEntry와 동일한 구조)
+export type ConsoleEntry = ConsoleEntryKind & {
+  level: LogLevel;
+  timestamp: number;
+  location: SourceLocation | null;
+  args: JsValuePreview[];
+  message: string;
+  spans: StyledSpan[];
+  groupDepth: number;
+};
+
+// 스크립트에서 던진 예외 (Rust의 ScriptError와 동일)
+export interface ScriptError {
+  name: string | null;
+  message: string;
+  frames: StackFrame[];
+  // 사용자 코드에서 예외가 발생한 위치 (1부터 시작)
+  location: SourceLocation | null;
+  sourceLine: string | null;
+  cause: ScriptError | null;
+}
+
 export interface JsExecutionResult {
   code: string;
   result: string;
   timestamp: string;
   success: boolean;
   error?: string;
+  error_kind?: JsErrorKind | null;
+  exception?: ScriptError | null;
+  value?: JsValuePreview | null;
+  entries?: ConsoleEntry[];
 }
 
 export interface CodeEditorProps {
@@ -13,12 +132,18 @@ export interface CodeEditorProps {
   onChange: (value: string) => void;
   onExecute: (code?: string) => void;
   language?: string;
+  // 에디터 모델 경로 (확장자로 JSX/TSX 구문 인식 여부가 결정됨)
+  path?: string;
   theme?: string;
+  // 마지막 실행에서 던진 예외 (해당 줄에 표시)
+  error?: ScriptError | null;
 }
 
 export interface OutputPanelProps {
   result: JsExecutionResult | null;
   isExecuting: boolean;
+  // 실행 도중 실시간으로 받은 콘솔 출력
+  liveEntries?: ConsoleEntry[];
 }
 
 export type LintSeverity = 'error' | 'warning' | 'info' | 'hint';
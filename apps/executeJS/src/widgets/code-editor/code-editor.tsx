@@ -8,7 +8,12 @@ import babel from 'prettier/plugins/babel';
 import estree from 'prettier/plugins/estree';
 import typescript from 'prettier/plugins/typescript';
 
-import { CodeEditorProps, LintResult, LintSeverity } from '@/shared';
+import {
+  CodeEditorProps,
+  LintResult,
+  LintSeverity,
+  ScriptError,
+} from '@/shared';
 
 const prettierOptions: PrettierOptions = {
   semi: true,
@@ -41,12 +46,17 @@ export const CodeEditor: React.FC<CodeEditorProps> = ({
   onChange,
   onExecute,
   language = 'javascript',
+  path,
   theme = 'vs-dark',
+  error,
 }) => {
   const editorRef = useRef<any>(null);
   const monacoRef = useRef<Monaco | null>(null);
   const disposablesRef = useRef<Array<{ dispose(): void }>>([]);
   const debounceTimeoutRef = useRef<NodeJS.Timeout | null>(null);
+  // 단축키는 마운트 시 한 번만 등록되므로 최신 onExecute를 ref로 참조
+  const onExecuteRef = useRef(onExecute);
+  onExecuteRef.current = onExecute;
 
   const validateCode = useCallback(async (model: any, version: number) => {
     if (debounceTimeoutRef.current) {
@@ -103,6 +113,40 @@ export const CodeEditor: React.FC<CodeEditorProps> = ({
     }, 500);
   }, []);
 
+  // 실행 중 던진 예외를 해당 줄에 표시하고 그 위치로 이동
+  const showRuntimeError = useCallback((scriptError?: ScriptError | null) => {
+    const editor = editorRef.current;
+    const monaco = monacoRef.current;
+    const model = editor?.getModel?.();
+    if (!monaco || !model) return;
+
+    const location = scriptError?.location;
+    if (!scriptError || !location) {
+      monaco.editor.setModelMarkers(model, 'runtime', []);
+      return;
+    }
+
+    const line = Math.min(location.line, model.getLineCount());
+    const name = scriptError.name ? `${scriptError.name}: ` : '';
+    monaco.editor.setModelMarkers(model, 'runtime', [
+      {
+        message: `${name}${scriptError.message}`,
+        severity: monaco.MarkerSeverity.Error,
+        startLineNumber: line,
+        startColumn: location.column,
+        endLineNumber: line,
+        endColumn: model.getLineMaxColumn(line),
+        source: 'runtime',
+      },
+    ]);
+    editor.revealLineInCenterIfOutsideViewport(line);
+    editor.setPosition({ lineNumber: line, column: location.column });
+  }, []);
+
+  useEffect(() => {
+    showRuntimeError(error);
+  }, [error, showRuntimeError]);
+
   // Monaco Editor 설정
   const handleEditorDidMount: EditorProps['onMount'] = (editor, monaco) => {
     try {
@@ -115,6 +159,15 @@ export const CodeEditor: React.FC<CodeEditorProps> = ({
         noSyntaxValidation: true,
       });
 
+      // TypeScript는 타입 검사 없이 타입만 제거해서 실행하므로 문법 검사만 사용
+      monaco.languages.typescript.typescriptDefaults.setDiagnosticsOptions({
+        noSemanticValidation: true,
+      });
+      monaco.languages.typescript.typescriptDefaults.setCompilerOptions({
+        ...monaco.languages.typescript.typescriptDefaults.getCompilerOptions(),
+        jsx: monaco.languages.typescript.JsxEmit.Preserve,
+      });
+
       // 이전 등록된 포맷터가 남아있는 경우, 먼저 해제
       if (disposablesRef.current.length > 0) {
         disposablesRef.current.forEach((disposable) => {
@@ -183,7 +236,7 @@ export const CodeEditor: React.FC<CodeEditorProps> = ({
         // Cmd+Enter 코드 실행
         editor.addCommand(monaco.KeyMod.CtrlCmd | monaco.KeyCode.Enter, () => {
           const currentValue = editor.getValue();
-          onExecute?.(currentValue);
+          onExecuteRef.current?.(currentValue);
         });
 
         // Cmd+Shift+F prettier 포맷 실행
@@ -202,6 +255,8 @@ export const CodeEditor: React.FC<CodeEditorProps> = ({
         const contentChangeDisposable = model.onDidChangeContent(() => {
           // Reset the markers
           monaco.editor.setModelMarkers(model, 'oxlint', []);
+          // 코드가 바뀌면 이전 실행의 예외 위치는 더 이상 맞지 않음
+          monaco.editor.setModelMarkers(model, 'runtime', []);
 
           // Send the code to the backend for validation
           validateCode(model, model.getVersionId());
@@ -216,6 +271,9 @@ export const CodeEditor: React.FC<CodeEditorProps> = ({
 
         // 초기 validation
         validateCode(model, model.getVersionId());
+
+        // 마운트 전에 받은 실행 결과의 예외 표시
+        showRuntimeError(error);
       } else {
         // model이 없는 경우 포맷터만 저장
         disposablesRef.current = [jsDisposable, tsDisposable];
@@ -290,6 +348,7 @@ export const CodeEditor: React.FC<CodeEditorProps> = ({
         height="100%"
         width="100%"
         language={language}
+        path={path}
         theme={theme}
         value={value}
         onChange={(newValue) => onChange(newValue || '')}
@@ -4,7 +4,39 @@ import type { OutputPanelProps } from '../../shared/types';
 export const OutputPanel: React.FC<OutputPanelProps> = ({
   result,
   isExecuting,
+  liveEntries,
 }) => {
+  // 실행 중이라도 이미 받은 출력이 있으면 바로 표시
+  if (isExecuting && liveEntries && liveEntries.length > 0) {
+    return (
+      <div className="h-full w-full bg-slate-900 overflow-y-auto">
+        <div className="p-6">
+          <pre className="text-sm font-mono whitespace-pre-wrap break-words overflow-x-auto">
+            {liveEntries.map((entry, index) => (
+              <div
+                key={index}
+                className={
+                  entry.level === 'error'
+                    ? 'text-red-400'
+                    : entry.level === 'warn'
+                      ? 'text-yellow-400'
+                      : 'text-green-400'
+                }
+                style={{ paddingLeft: `${entry.groupDepth * 2}ch` }}
+              >
+                {entry.message}
+              </div>
+            ))}
+          </pre>
+          <div className="flex items-center gap-3 mt-4 text-slate-400">
+            <div className="animate-spin w-4 h-4 border-2 border-blue-500 border-t-transparent rounded-full"></div>
+            <span className="text-xs font-medium">실행 중...</span>
+          </div>
+        </div>
+      </div>
+    );
+  }
+
   if (isExecuting) {
     return (
       <div className="h-full w-full p-6 bg-slate-900">
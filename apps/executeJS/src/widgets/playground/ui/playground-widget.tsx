@@ -10,25 +10,43 @@ import {
   Playground,
   usePlaygroundStore,
 } from '@/features/playground';
+import type { Language } from '@/shared';
+
+// 실행 언어별 Monaco 언어와 모델 확장자 (JSX/TSX는 확장자로 구문을 인식)
+const EDITOR_LANGUAGES: Record<
+  Language,
+  { monacoLanguage: string; extension: string }
+> = {
+  javascript: { monacoLanguage: 'javascript', extension: 'js' },
+  typescript: { monacoLanguage: 'typescript', extension: 'ts' },
+  jsx: { monacoLanguage: 'javascript', extension: 'jsx' },
+  tsx: { monacoLanguage: 'typescript', extension: 'tsx' },
+};
 
 interface PlaygroundProps {
   playground: Playground;
 }
 
 export const PlaygroundWidget: React.FC<PlaygroundProps> = ({ playground }) => {
-  const { id, isExecuting, result: executionResult } = playground;
+  const {
+    id,
+    isExecuting,
+    result: executionResult,
+    liveEntries,
+    language = 'javascript',
+  } = playground;
 
   const [code, setCode] = useState(
     executionResult?.code || DEFAULT_PLAYGROUND_CODE
   );
 
-  const { executeCode } = usePlaygroundStore();
+  const { executeCode, setLanguage } = usePlaygroundStore();
 
   // 코드 실행 핸들러
   const handleExecuteCode = (codeToExecute?: string) => {
     const codeToRun = codeToExecute || code;
     if (codeToRun.trim()) {
-      executeCode({ code: codeToRun, playgroundId: id });
+      executeCode({ code: codeToRun, playgroundId: id, language });
     }
   };
 
@@ -46,6 +64,22 @@ export const PlaygroundWidget: React.FC<PlaygroundProps> = ({ playground }) => {
         </div>
 
         <div className="flex items-center gap-2">
+          <select
+            value={language}
+            onChange={(e) =>
+              setLanguage({
+                playgroundId: id,
+                language: e.target.value as Language,
+              })
+            }
+            disabled={isExecuting}
+            className="px-2 py-1.5 bg-slate-800 border border-slate-700 text-slate-300 text-sm rounded-md"
+          >
+            <option value="javascript">JavaScript</option>
+            <option value="typescript">TypeScript</option>
+            <option value="jsx">JSX</option>
+            <option value="tsx">TSX</option>
+          </select>
           <button
             onClick={() => handleExecuteCode()}
             disabled={isExecuting || !code.trim()}
@@ -82,8 +116,10 @@ export const PlaygroundWidget: React.FC<PlaygroundProps> = ({ playground }) => {
                   value={code}
                   onChange={handleCodeChange}
                   onExecute={handleExecuteCode}
-                  language="javascript"
+                  language={EDITOR_LANGUAGES[language].monacoLanguage}
+                  path={`playground-${id}.${EDITOR_LANGUAGES[language].extension}`}
                   theme="vs-dark"
+                  error={executionResult?.exception}
                 />
               </div>
             </div>
@@ -104,6 +140,7 @@ export const PlaygroundWidget: React.FC<PlaygroundProps> = ({ playground }) => {
                 <OutputPanel
                   result={executionResult}
                   isExecuting={isExecuting}
+                  liveEntries={liveEntries}
                 />
               </div>
             </div>
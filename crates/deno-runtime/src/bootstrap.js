@@ -2,57 +2,133 @@
 const { core } = Deno;
 const { ops } = core;
 
-// console 객체 정의
-globalThis.console = {
-  log: (...args) => {
-    const message = args
-      .map((arg) => {
-        if (typeof arg === 'object') {
-          return JSON.stringify(arg, null, 2);
-        }
-        return String(arg);
-      })
-      .join(' ');
-    ops.op_console_log(message);
-  },
-
-  error: (...args) => {
-    const message = args
-      .map((arg) => {
-        if (typeof arg === 'object') {
-          return JSON.stringify(arg, null, 2);
-        }
-        return String(arg);
-      })
-      .join(' ');
-    ops.op_custom_print(message, true);
-  },
-
-  warn: (...args) => {
-    const message = args
-      .map((arg) => {
-        if (typeof arg === 'object') {
-          return JSON.stringify(arg, null, 2);
+// console 객체 정의 (WHATWG Console 표준의 메서드 전체)
+// 인자는 문자열로 바꾸지 않고 그대로 넘겨 Rust 쪽에서 구조화된 값으로 보관
+globalThis.console = (() => {
+  const emit =
+    (level) =>
+    (...args) => {
+      ops.op_console_log(level, args);
+    };
+
+  const isPrimitive = (value) => value === null || (typeof value !== 'object' && typeof value !== 'function');
+
+  // console.table: 행마다 [인덱스, 열 이름별 값, 단일 값 여부, 단일 값]으로 정리한 뒤
+  // 열 순서에 맞춘 칸 배열로 넘김 (값이 없는 칸은 비워 둠)
+  const table = (data, properties) => {
+    if (isPrimitive(data)) {
+      ops.op_console_log('log', [data]);
+      return;
+    }
+
+    const isIterable = data instanceof Map || data instanceof Set;
+    const rows = isIterable
+      ? [...data].map((item, index) =>
+          data instanceof Map
+            ? [String(index), new Map([['Key', item[0]]]), true, item[1]]
+            : [String(index), new Map(), true, item]
+        )
+      : Object.keys(data).map((index) => {
+          const value = data[index];
+          if (isPrimitive(value)) {
+            return [index, new Map(), true, value];
+          }
+          const keys = (properties ?? Object.keys(value)).filter((key) => key in value);
+          return [index, new Map(keys.map((key) => [key, value[key]])), false];
+        });
+
+    const columns = Array.isArray(properties) ? properties.map(String) : [];
+    for (const [, cells] of rows) {
+      for (const key of cells.keys()) {
+        if (!columns.includes(key)) {
+          columns.push(key);
         }
-        return String(arg);
-      })
-      .join(' ');
-    ops.op_custom_print(`[WARN] ${message}`, false);
-  },
-
-  info: (...args) => {
-    const message = args
-      .map((arg) => {
-        if (typeof arg === 'object') {
-          return JSON.stringify(arg, null, 2);
+      }
+    }
+    const valuesColumn = rows.some((row) => row[2]) ? columns.push('Values') - 1 : -1;
+
+    ops.op_console_table(
+      isIterable ? '(iteration index)' : '(index)',
+      columns,
+      rows.map(([index, cells, hasValue, value]) => {
+        const row = [];
+        columns.forEach((column, i) => {
+          if (cells.has(column)) {
+            row[i] = cells.get(column);
+          }
+        });
+        if (hasValue) {
+          row[valuesColumn] = value;
         }
-        return String(arg);
+        return [index, row];
       })
-      .join(' ');
-    ops.op_custom_print(`[INFO] ${message}`, false);
-  },
+    );
+  };
+
+  return {
+    log: emit('log'),
+    error: emit('error'),
+    warn: emit('warn'),
+    info: emit('info'),
+    debug: emit('debug'),
+    dirxml: emit('log'),
+    trace: (...args) => ops.op_console_trace(args),
+    assert: (condition = false, ...data) => {
+      if (condition) {
+        return;
+      }
+      if (data.length === 0) {
+        data.push('Assertion failed');
+      } else if (typeof data[0] === 'string') {
+        data[0] = `Assertion failed: ${data[0]}`;
+      } else {
+        data.unshift('Assertion failed');
+      }
+      ops.op_console_log('error', data);
+    },
+    table,
+    dir: (item, options) => {
+      const depth = options?.depth === null ? Infinity : (options?.depth ?? 2);
+      ops.op_console_dir(item, Number(depth));
+    },
+    group: (...label) => ops.op_console_group(label, false),
+    groupCollapsed: (...label) => ops.op_console_group(label, true),
+    groupEnd: () => ops.op_console_group_end(),
+    count: (label = 'default') => ops.op_console_count(String(label), false),
+    countReset: (label = 'default') => ops.op_console_count(String(label), true),
+    time: (label = 'default') => ops.op_console_time(String(label)),
+    timeLog: (label = 'default', ...data) => ops.op_console_time_log(String(label), data, false),
+    timeEnd: (label = 'default') => ops.op_console_time_log(String(label), [], true),
+    clear: () => ops.op_console_clear(),
+    // 프로파일링은 지원하지 않음
+    profile: () => {},
+    profileEnd: () => {},
+    timeStamp: () => {},
+  };
+})();
+
+// 타이머 함수 정의 (deno_core의 타이머 큐 사용)
+const wrapTimerCallback = (callback, args) => {
+  if (typeof callback !== 'function') {
+    // 브라우저처럼 문자열 콜백은 전역 스코프에서 평가
+    const source = String(callback);
+    return () => (0, eval)(source);
+  }
+  return args.length === 0 ? callback : () => callback.apply(globalThis, args);
 };
 
+globalThis.setTimeout = (callback, timeout = 0, ...args) =>
+  core.queueUserTimer(core.getTimerDepth() + 1, false, Number(timeout) || 0, wrapTimerCallback(callback, args));
+
+globalThis.setInterval = (callback, timeout = 0, ...args) =>
+  core.queueUserTimer(core.getTimerDepth() + 1, true, Number(timeout) || 0, wrapTimerCallback(callback, args));
+
+globalThis.clearTimeout = (id = 0) => {
+  core.cancelTimer(Number(id));
+};
+
+globalThis.clearInterval = globalThis.clearTimeout;
+
 // alert 함수 정의
 globalThis.alert = (message) => {
   ops.op_alert(String(message));
@@ -63,72 +139,112 @@ globalThis.print = (message, isErr = false) => {
   ops.op_custom_print(String(message), isErr);
 };
 
-// npm 모듈 지원을 위한 require 함수 정의 (시뮬레이션)
-globalThis.require = (moduleName) => {
-  // 간단한 npm 모듈 시뮬레이션
-  const modules = {
-    lodash: {
-      map: (array, iteratee) => {
-        if (!Array.isArray(array)) {
-          throw new Error('First argument must be an array');
-        }
-        return array.map(iteratee);
-      },
-      filter: (array, predicate) => {
-        if (!Array.isArray(array)) {
-          throw new Error('First argument must be an array');
-        }
-        return array.filter(predicate);
-      },
-      reduce: (array, iteratee, accumulator) => {
-        if (!Array.isArray(array)) {
-          throw new Error('First argument must be an array');
-        }
-        return array.reduce(iteratee, accumulator);
+// CommonJS 모듈 로더 (npm 패키지의 CommonJS 파일과 사용자 코드의 require)
+(() => {
+  const cache = Object.create(null);
+
+  const dirname = (filename) =>
+    filename.slice(0, Math.max(filename.lastIndexOf('/'), filename.lastIndexOf('\\'), 0)) || '/';
+
+  // evalContext는 스크립트 이름으로 URL을 받음 (Windows 경로는 `file:///C:/...`)
+  const toFileUrl = (filename) =>
+    filename.startsWith('/') ? `file://${encodeURI(filename)}` : `file:///${encodeURI(filename.replaceAll('\\', '/'))}`;
+
+  // ES 모듈 facade가 이름 export를 다시 읽도록 등록한 함수 (module → 함수 목록)
+  const refreshers = new WeakMap();
+
+  // exports 객체에 값을 쓰면 facade에 알리는 Proxy
+  // (대상 객체와 속성 설명자는 그대로 두고 쓰기만 전달)
+  const observeExports = (module, target) => {
+    const notify = () => refreshers.get(module)?.forEach((refresh) => refresh(module.exports));
+    return new Proxy(target, {
+      set(target, key, value) {
+        const result = Reflect.set(target, key, value);
+        notify();
+        return result;
       },
-      find: (array, predicate) => {
-        if (!Array.isArray(array)) {
-          throw new Error('First argument must be an array');
-        }
-        return array.find(predicate);
+      defineProperty(target, key, descriptor) {
+        const result = Reflect.defineProperty(target, key, descriptor);
+        notify();
+        return result;
       },
-      chunk: (array, size) => {
-        if (!Array.isArray(array)) {
-          throw new Error('First argument must be an array');
-        }
-        const chunks = [];
-        for (let i = 0; i < array.length; i += size) {
-          chunks.push(array.slice(i, i + size));
-        }
-        return chunks;
+      deleteProperty(target, key) {
+        const result = Reflect.deleteProperty(target, key);
+        notify();
+        return result;
       },
-    },
-    moment: {
-      now: () => new Date(),
-      format: (date, format) => {
-        if (!(date instanceof Date)) {
-          date = new Date(date);
+    });
+  };
+
+  // 절대 경로의 파일을 CommonJS 모듈로 실행하고 module.exports 반환
+  // (`observed`면 exports 변경을 facade에 알림)
+  const load = (filename, parent, observed = false) => {
+    const cached = cache[filename];
+    if (cached) {
+      return cached.exports;
+    }
+
+    const module = {
+      id: filename,
+      filename,
+      path: dirname(filename),
+      exports: {},
+      parent,
+      children: [],
+      loaded: false,
+    };
+    if (observed) {
+      module.exports = observeExports(module, module.exports);
+    }
+    module.require = createRequire(filename);
+    parent?.children.push(module);
+    // 순환 require는 실행 중인 모듈의 현재 exports를 받음
+    cache[filename] = module;
+
+    try {
+      const source = ops.op_require_read(filename);
+      if (filename.endsWith('.json')) {
+        module.exports = JSON.parse(source);
+      } else {
+        // 줄 번호가 원본과 같도록 래퍼는 첫 줄에 붙이고, 래퍼 안에서는 문법 오류인
+        // 첫 줄의 `#!`는 같은 길이의 주석으로 바꿈
+        const body = source.startsWith('#!') ? `//${source.slice(2)}` : source;
+        const wrapper = `(function (exports, require, module, __filename, __dirname) {${body}\n})`;
+        const [fn, error] = core.evalContext(wrapper, toFileUrl(filename));
+        if (error) {
+          throw error.thrown;
         }
-        return date.toISOString();
-      },
-    },
-    uuid: {
-      v4: () => {
-        return 'xxxxxxxx-xxxx-4xxx-yxxx-xxxxxxxxxxxx'.replace(/[xy]/g, function (c) {
-          const r = (Math.random() * 16) | 0;
-          const v = c == 'x' ? r : (r & 0x3) | 0x8;
-          return v.toString(16);
-        });
-      },
-    },
+        fn.call(module.exports, module.exports, module.require, module, filename, module.path);
+      }
+    } catch (error) {
+      delete cache[filename];
+      throw error;
+    }
+
+    module.loaded = true;
+    return module.exports;
   };
 
-  if (modules[moduleName]) {
-    return modules[moduleName];
+  // referrer 파일 기준으로 모듈을 찾는 require (referrer가 비어 있으면 사용자 코드)
+  function createRequire(referrer) {
+    const require = (specifier) => load(ops.op_require_resolve(String(specifier), referrer), cache[referrer]);
+    require.resolve = (specifier) => ops.op_require_resolve(String(specifier), referrer);
+    require.cache = cache;
+    return require;
   }
 
-  throw new Error(`Cannot find module '${moduleName}'. Available modules: ${Object.keys(modules).join(', ')}`);
-};
+  globalThis.require = createRequire('');
+  // ES 모듈에서 CommonJS 파일을 import할 때 모듈 로더가 만든 코드에서 호출
+  // (`refresh`는 exports가 바뀔 때 facade가 이름 export를 module.exports에서 다시 읽는 함수)
+  globalThis[Symbol.for('executejs.require')] = (filename, refresh) => {
+    const exports = load(filename, undefined, true);
+    const module = cache[filename];
+    if (refresh && module) {
+      refreshers.set(module, [...(refreshers.get(module) ?? []), refresh]);
+    }
+    return exports;
+  };
+})();
 
 // 기본적인 전역 객체들 정의
 if (typeof globalThis.window === 'undefined') {
@@ -139,7 +255,7 @@ if (typeof globalThis.global === 'undefined') {
   globalThis.global = globalThis;
 }
 
-// Node.js 스타일 모듈 시스템 지원
+// 사용자 스크립트에서 쓰는 module/exports (CommonJS 모듈은 모듈마다 따로 받음)
 if (typeof globalThis.module === 'undefined') {
   globalThis.module = { exports: {} };
 }
@@ -0,0 +1,503 @@
+use crate::node_resolver::{self, ImportTarget};
+use crate::npm_resolver::parse_package_specifier;
+use crate::{CancelHandle, DependencyGraph, InstalledPackage, NpmResolver};
+use anyhow::{Context, Result};
+use deno_ast::swc::ast::{
+    AssignTarget, CallExpr, Callee, Expr, Lit, MemberExpr, MemberProp, ModuleItem, Prop, PropName,
+    PropOrSpread, SimpleAssignTarget, Stmt,
+};
+use deno_ast::swc::ecma_visit::{Visit, VisitWith};
+use deno_ast::{MediaType, ParseParams, ParsedSource, ProgramRef};
+use deno_core::error::AnyError;
+use deno_core::{op2, JsRuntime, ModuleSpecifier, OpState};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// bootstrap.js가 CommonJS 모듈 로더를 등록하는 전역 심볼 키
+const REQUIRE_SYMBOL: &str = "executejs.require";
+
+/// `require`의 모듈 해석 (OpState에 보관)
+///
+/// ES 모듈 로더와 같은 캐시와 의존성 그래프를 쓰지만, `exports` 조건은 `import` 대신
+/// `require`를 사용합니다.
+#[derive(Clone)]
+pub(crate) struct RequireResolver {
+    npm_resolver: NpmResolver,
+    dependency_graph: DependencyGraph,
+    /// `require` 중에 설치를 기다리다가 타임아웃이나 취소가 오면 중단
+    cancel_handle: CancelHandle,
+    /// 실행 전 설치에 실패한 패키지의 오류 (`이름@버전` → 메시지)
+    ///
+    /// `try`/`catch` 안이나 실행되지 않는 분기의 `require`일 수 있으므로 실행을 막지 않고,
+    /// 그 `require`를 호출할 때 던집니다.
+    failures: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl RequireResolver {
+    pub(crate) fn new(
+        npm_resolver: NpmResolver,
+        dependency_graph: DependencyGraph,
+        cancel_handle: CancelHandle,
+    ) -> Self {
+        let conditions = npm_resolver
+            .conditions()
+            .iter()
+            .map(|condition| match condition.as_str() {
+                "import" => "require".to_string(),
+                _ => condition.clone(),
+            })
+            .collect();
+        Self {
+            npm_resolver: npm_resolver.with_conditions(conditions),
+            dependency_graph,
+            cancel_handle,
+            failures: Arc::default(),
+        }
+    }
+
+    /// `require(specifier)`가 가리키는 파일의 절대 경로
+    ///
+    /// `referrer`가 비어 있으면(사용자 코드) 현재 디렉토리 기준으로 해석합니다. 실행 전에
+    /// 설치하지 못한 npm 패키지(로컬 CommonJS 파일의 `require`, 실행 중에 정해지는 이름)는
+    /// 여기서 설치합니다.
+    fn resolve(&self, specifier: &str, referrer: &str) -> Result<PathBuf> {
+        let referrer_path = if referrer.is_empty() {
+            std::env::current_dir()?.join("[require]")
+        } else {
+            PathBuf::from(referrer)
+        };
+        let not_found = || anyhow::anyhow!("Cannot find module '{}'", specifier);
+
+        if let Some(spec) = specifier.strip_prefix("npm:") {
+            return self.resolve_npm(spec);
+        }
+        if specifier.starts_with("node:") {
+            anyhow::bail!("Node.js 내장 모듈은 지원하지 않습니다: {}", specifier);
+        }
+        if specifier.starts_with("./") || specifier.starts_with("../") || specifier.starts_with('/')
+        {
+            let base = referrer_path.parent().unwrap_or(Path::new("/"));
+            return node_resolver::resolve_file(&base.join(specifier)).ok_or_else(not_found);
+        }
+        if specifier.starts_with('#') {
+            let target = node_resolver::resolve_imports(
+                specifier,
+                &referrer_path,
+                self.npm_resolver.conditions(),
+            )?;
+            return match target {
+                ImportTarget::File(path) if path.is_file() => Ok(path),
+                ImportTarget::File(_) => Err(not_found()),
+                ImportTarget::Package(target) => self.resolve(&target, referrer),
+            };
+        }
+
+        // 패키지 안에서는 선언된 의존성 버전, 사용자 코드에서는 설치된 버전이나 최신 버전
+        let npm_specifier = self
+            .npm_resolver
+            .resolve_package_import(specifier, &referrer_path, &self.dependency_graph)
+            .unwrap_or_else(|| format!("npm:{}", specifier));
+        self.resolve_npm(&npm_specifier["npm:".len()..])
+    }
+
+    /// `이름@버전/하위경로` 형식의 npm 패키지 파일 찾기
+    fn resolve_npm(&self, spec: &str) -> Result<PathBuf> {
+        let (name, version, subpath) = parse_package_specifier(spec);
+        let version = match self.dependency_graph.find(&name, version.as_deref()) {
+            Some(version) => version,
+            None => {
+                let key = package_key(&name, version.as_deref());
+                if let Some(error) = self.failures.lock().unwrap().get(&key) {
+                    anyhow::bail!("Cannot find module '{}': {}", spec, error);
+                }
+                self.install_blocking(&name, version.as_deref())
+                    .with_context(|| format!("Cannot find module '{}'", spec))?
+                    .version
+            }
+        };
+        let package = self.npm_resolver.installed(&name, &version);
+        node_resolver::resolve_require_package(
+            &package.dir.join("package"),
+            subpath.as_deref(),
+            self.npm_resolver.conditions(),
+        )
+    }
+
+    /// 실행 전에 설치하지 않은 패키지를 `require` 중에 의존성과 함께 설치
+    ///
+    /// `require`는 동기 op라서 설치가 끝날 때까지 기다려야 합니다. 설치는 별도 스레드의
+    /// 런타임에서 진행하고 op는 `CancelHandle::race`로 결과를 기다리므로, 타임아웃이나
+    /// 취소가 오면 설치를 기다리지 않고 바로 중단됩니다.
+    fn install_blocking(&self, name: &str, version: Option<&str>) -> Result<InstalledPackage> {
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        let npm_resolver = self.npm_resolver.clone();
+        let dependency_graph = self.dependency_graph.clone();
+        let (name, version) = (name.to_string(), version.map(str::to_string));
+        std::thread::spawn(move || {
+            let result = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(anyhow::Error::from)
+                .and_then(|rt| {
+                    rt.block_on(npm_resolver.install_with_dependencies(
+                        &name,
+                        version.as_deref(),
+                        &dependency_graph,
+                    ))
+                });
+            let _ = sender.send(result);
+        });
+        futures::executor::block_on(self.cancel_handle.race(async {
+            receiver
+                .await
+                .context("패키지 설치 스레드가 종료되었습니다")?
+        }))
+    }
+
+    /// `require`가 가리키는 npm 패키지를 의존성과 함께 설치 (이미 설치되어 있으면 건너뜀)
+    ///
+    /// 실패하면 실행을 중단하지 않고 오류를 기록해 두었다가 그 `require`를 호출할 때 던집니다.
+    async fn install(&self, specifier: &str) {
+        let spec = match specifier.strip_prefix("npm:") {
+            Some(spec) => spec,
+            None if is_bare_specifier(specifier) => specifier,
+            None => return,
+        };
+        let (name, version, _) = parse_package_specifier(spec);
+        if self
+            .dependency_graph
+            .find(&name, version.as_deref())
+            .is_some()
+        {
+            return;
+        }
+        if let Err(error) = self
+            .npm_resolver
+            .install_with_dependencies(&name, version.as_deref(), &self.dependency_graph)
+            .await
+        {
+            self.failures.lock().unwrap().insert(
+                package_key(&name, version.as_deref()),
+                format!("{:#}", error),
+            );
+        }
+    }
+}
+
+/// 설치 실패를 기록하는 키 (`이름@버전`, 버전이 없으면 `latest`)
+fn package_key(name: &str, version: Option<&str>) -> String {
+    format!("{}@{}", name, version.unwrap_or("latest"))
+}
+
+/// 상대/절대 경로, `#` specifier, URL이 아닌 패키지 이름
+fn is_bare_specifier(specifier: &str) -> bool {
+    !(specifier.starts_with("./")
+        || specifier.starts_with("../")
+        || specifier.starts_with('/')
+        || specifier.starts_with('#')
+        || ModuleSpecifier::parse(specifier).is_ok())
+}
+
+/// 코드가 `require("...")`로 불러오는 npm 패키지를 실행 전에 설치
+///
+/// 문자열 리터럴로 지정한 패키지는 `require` 중에 설치를 기다리지 않도록 이벤트 루프처럼
+/// `CancelHandle::race` 안에서 미리 설치합니다. 설치에 실패한 패키지는 실행을 막지 않고
+/// 해당 `require`를 호출할 때 오류를 던집니다. `code`는 변환을 마친 JavaScript입니다.
+pub(crate) async fn install_required_packages(
+    js_runtime: &mut JsRuntime,
+    code: &str,
+) -> Result<()> {
+    let Some(resolver) = js_runtime
+        .op_state()
+        .borrow()
+        .try_borrow::<RequireResolver>()
+        .cloned()
+    else {
+        return Ok(());
+    };
+    // 이전 평가(REPL)에서 실패한 패키지는 다시 시도
+    resolver.failures.lock().unwrap().clear();
+    for specifier in require_specifiers(code) {
+        resolver.install(&specifier).await;
+    }
+    Ok(())
+}
+
+/// `require("...")` 호출의 문자열 리터럴 인자
+fn require_specifiers(code: &str) -> BTreeSet<String> {
+    struct RequireCalls(BTreeSet<String>);
+
+    impl Visit for RequireCalls {
+        fn visit_call_expr(&mut self, call: &CallExpr) {
+            if let Callee::Expr(callee) = &call.callee {
+                if matches!(&**callee, Expr::Ident(ident) if &*ident.sym == "require") {
+                    if let Some(Expr::Lit(Lit::Str(specifier))) =
+                        call.args.first().map(|arg| &*arg.expr)
+                    {
+                        self.0
+                            .extend(specifier.value.as_atom().map(ToString::to_string));
+                    }
+                }
+            }
+            call.visit_children_with(self);
+        }
+    }
+
+    let Some(parsed) = parse("[require]", code, true).or_else(|| parse("[require]", code, false))
+    else {
+        return BTreeSet::new();
+    };
+    let mut calls = RequireCalls(BTreeSet::new());
+    match parsed.program_ref() {
+        ProgramRef::Module(module) => module.visit_with(&mut calls),
+        ProgramRef::Script(script) => script.visit_with(&mut calls),
+    }
+    calls.0
+}
+
+/// `require`할 파일의 절대 경로
+#[op2]
+#[string]
+pub(crate) fn op_require_resolve(
+    state: &mut OpState,
+    #[string] specifier: String,
+    #[string] referrer: String,
+) -> Result<String, AnyError> {
+    let resolver = state
+        .try_borrow::<RequireResolver>()
+        .context("npm 리졸버를 사용할 수 없습니다")?;
+    let path = resolver.resolve(&specifier, &referrer)?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// CommonJS 모듈 소스 읽기
+#[op2]
+#[string]
+pub(crate) fn op_require_read(#[string] path: String) -> Result<String, AnyError> {
+    fs::read_to_string(&path).with_context(|| format!("Cannot find module '{}'", path))
+}
+
+/// 파일이 CommonJS 모듈인지 판단
+///
+/// `.cjs`는 CommonJS, `.mjs`는 ES 모듈이고, `.js`는 가장 가까운 package.json의
+/// `"type": "module"`이면 ES 모듈입니다. 그 외의 `.js`는 `module` 필드처럼 ESM 파일을
+/// 가리키는 경우가 많으므로 `import`/`export` 구문이 없을 때만 CommonJS로 봅니다.
+pub(crate) fn is_commonjs(path: &Path, code: &str) -> bool {
+    match MediaType::from_path(path) {
+        MediaType::Cjs => true,
+        MediaType::JavaScript => {
+            let is_module_package = node_resolver::package_scope(path)
+                .and_then(|scope| fs::read_to_string(scope.join("package.json")).ok())
+                .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+                .is_some_and(|package_json| package_json["type"] == "module");
+            !is_module_package && !has_module_syntax(path, code)
+        }
+        _ => false,
+    }
+}
+
+/// CommonJS 모듈을 ES 모듈로 가져오기 위한 모듈 코드
+///
+/// `default`는 `module.exports` 전체이고, 코드에서 찾은 `exports.이름` 할당은 같은
+/// 이름의 export로 내보냅니다. 로더는 이 facade로 처음 불러온 모듈의 `exports` 객체에
+/// 값을 쓸 때마다 `__refresh`를 호출하고, facade는 이름 export를 `module.exports`에서
+/// 다시 읽습니다 (`require`로 먼저 불러온 모듈은 가져올 때의 값 유지).
+pub(crate) fn esm_facade(path: &Path, code: &str) -> String {
+    let path = path.to_string_lossy();
+    let names = export_names(&path, code);
+    let mut facade = String::new();
+    let mut refresh = String::new();
+    let mut exports = Vec::new();
+    for (index, name) in names.iter().enumerate() {
+        facade.push_str(&format!("let __export{index};\n"));
+        refresh.push_str(&format!(
+            "  __export{index} = exports[{key}];\n",
+            key = serde_json::Value::from(name.as_str()),
+        ));
+        exports.push(format!("__export{index} as {name}"));
+    }
+    facade.push_str(&format!(
+        "const __refresh = (exports) => {{\n{refresh}}};\n\
+         const mod = globalThis[Symbol.for({})]({}, __refresh);\n\
+         export default mod;\n\
+         __refresh(mod);\n",
+        serde_json::Value::from(REQUIRE_SYMBOL),
+        serde_json::Value::from(path.as_ref()),
+    ));
+    if !exports.is_empty() {
+        facade.push_str(&format!("export {{ {} }};\n", exports.join(", ")));
+    }
+    facade
+}
+
+/// CommonJS 코드에서 정적으로 찾을 수 있는 export 이름
+///
+/// 최상위의 `exports.이름 = ...`, `module.exports.이름 = ...`,
+/// `Object.defineProperty(exports, "이름", ...)`, `module.exports = { 이름, ... }`를 찾습니다.
+fn export_names(path: &str, code: &str) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    let Some(parsed) = parse(path, code, false) else {
+        return names;
+    };
+    for stmt in &parsed.program_ref().unwrap_script().body {
+        if let Stmt::Expr(expr) = stmt {
+            collect_exports(&expr.expr, &mut names);
+        }
+    }
+    names.retain(|name| name != "default" && name != "__esModule" && is_identifier_name(name));
+    names
+}
+
+fn collect_exports(expr: &Expr, names: &mut BTreeSet<String>) {
+    match expr {
+        Expr::Paren(paren) => collect_exports(&paren.expr, names),
+        Expr::Seq(seq) => seq
+            .exprs
+            .iter()
+            .for_each(|expr| collect_exports(expr, names)),
+        Expr::Assign(assign) => {
+            if let AssignTarget::Simple(SimpleAssignTarget::Member(member)) = &assign.left {
+                if is_exports_object(&member.obj) {
+                    names.extend(member_name(&member.prop));
+                } else if is_module_exports(member) {
+                    if let Expr::Object(object) = &*assign.right {
+                        names.extend(object.props.iter().filter_map(object_key));
+                    }
+                }
+            }
+            // `exports.a = exports.b = void 0` 같은 연속 할당
+            collect_exports(&assign.right, names);
+        }
+        Expr::Call(call) => {
+            let Callee::Expr(callee) = &call.callee else {
+                return;
+            };
+            let is_define_property = matches!(&**callee, Expr::Member(member)
+                if matches!(&*member.obj, Expr::Ident(object) if &*object.sym == "Object")
+                    && member_name(&member.prop).as_deref() == Some("defineProperty"));
+            if is_define_property && call.args.len() >= 2 && is_exports_object(&call.args[0].expr) {
+                if let Expr::Lit(Lit::Str(name)) = &*call.args[1].expr {
+                    names.extend(name.value.as_atom().map(ToString::to_string));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `exports` 또는 `module.exports`
+fn is_exports_object(expr: &Expr) -> bool {
+    match expr {
+        Expr::Ident(ident) => &*ident.sym == "exports",
+        Expr::Member(member) => is_module_exports(member),
+        _ => false,
+    }
+}
+
+fn is_module_exports(member: &MemberExpr) -> bool {
+    matches!(&*member.obj, Expr::Ident(ident) if &*ident.sym == "module")
+        && member_name(&member.prop).as_deref() == Some("exports")
+}
+
+fn member_name(prop: &MemberProp) -> Option<String> {
+    match prop {
+        MemberProp::Ident(ident) => Some(ident.sym.to_string()),
+        MemberProp::Computed(computed) => match &*computed.expr {
+            Expr::Lit(Lit::Str(name)) => name.value.as_atom().map(ToString::to_string),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn object_key(prop: &PropOrSpread) -> Option<String> {
+    let PropOrSpread::Prop(prop) = prop else {
+        return None;
+    };
+    let key = match &**prop {
+        Prop::Shorthand(ident) => return Some(ident.sym.to_string()),
+        Prop::KeyValue(prop) => &prop.key,
+        Prop::Method(prop) => &prop.key,
+        Prop::Getter(prop) => &prop.key,
+        _ => return None,
+    };
+    match key {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(name) => name.value.as_atom().map(ToString::to_string),
+        _ => None,
+    }
+}
+
+/// `export { x as 이름 }`에 그대로 쓸 수 있는 이름인지
+fn is_identifier_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// `import`/`export` 선언이 있는지
+fn has_module_syntax(path: &Path, code: &str) -> bool {
+    parse(&path.to_string_lossy(), code, true).is_some_and(|parsed| {
+        parsed
+            .program_ref()
+            .unwrap_module()
+            .body
+            .iter()
+            .any(|item| matches!(item, ModuleItem::ModuleDecl(_)))
+    })
+}
+
+fn parse(path: &str, code: &str, is_module: bool) -> Option<ParsedSource> {
+    let specifier = ModuleSpecifier::from_file_path(path)
+        .or_else(|_| ModuleSpecifier::parse("file:///module.js"))
+        .ok()?;
+    let params = ParseParams {
+        specifier,
+        text: code.into(),
+        media_type: MediaType::JavaScript,
+        capture_tokens: false,
+        scope_analysis: false,
+        maybe_syntax: None,
+    };
+    let parsed = if is_module {
+        deno_ast::parse_module(params)
+    } else {
+        deno_ast::parse_script(params)
+    }
+    .ok()?;
+    parsed.diagnostics().is_empty().then_some(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_require_specifiers() {
+        let code = "const _ = require('lodash');\n\
+                    function load() { return require(\"npm:uuid@9\").v4; }\n\
+                    const local = require('./local');\n\
+                    const dynamic = require(name);\n\
+                    const text = 'require(\"in-string\")';\n";
+        let specifiers: Vec<_> = require_specifiers(code).into_iter().collect();
+        assert_eq!(specifiers, ["./local", "lodash", "npm:uuid@9"]);
+
+        // top-level await와 import 선언이 있는 코드도 모듈로 파싱해서 찾음
+        let code = "import x from 'npm:a';\nconst b = require('b');\nawait x;";
+        assert!(require_specifiers(code).contains("b"));
+    }
+
+    #[test]
+    fn test_is_bare_specifier() {
+        assert!(is_bare_specifier("lodash"));
+        assert!(is_bare_specifier("@scope/pkg/sub"));
+        assert!(!is_bare_specifier("./local"));
+        assert!(!is_bare_specifier("/abs/path.js"));
+        assert!(!is_bare_specifier("#internal"));
+        assert!(!is_bare_specifier("node:fs"));
+    }
+}
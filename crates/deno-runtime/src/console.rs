@@ -0,0 +1,504 @@
+use crate::inspect::number_to_string;
+use crate::output::{ConsoleEntryKind, ConsoleTable, ConsoleTableRow, StackFrame};
+use crate::{
+    inspect, push_entry, ConsoleEntry, InspectOptions, JsValuePreview, LogLevel, SourceLocation,
+    StyledText, BOOTSTRAP_SCRIPT_NAME,
+};
+use deno_core::error::AnyError;
+use deno_core::{op2, v8, OpState};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// 호출 스택에서 읽을 최대 프레임 수
+const MAX_STACK_FRAMES: usize = 16;
+
+/// console 메서드 사이에서 유지되는 상태 (그룹 깊이, count, time)
+///
+/// JsRuntime마다 하나씩 OpState에 보관되므로 REPL 세션에서는 스니펫 사이에도 유지됩니다.
+#[derive(Default)]
+pub(crate) struct ConsoleState {
+    group_depth: usize,
+    counts: HashMap<String, u64>,
+    timers: HashMap<String, Instant>,
+}
+
+/// 현재 그룹 깊이를 기록하여 출력 수집기에 추가
+fn emit(state: &mut OpState, mut entry: ConsoleEntry) {
+    entry.group_depth = state.borrow::<ConsoleState>().group_depth;
+    push_entry(state, entry);
+}
+
+/// 문자열 하나로 된 메시지 (count, time 등)
+fn message_entry(
+    level: LogLevel,
+    location: Option<SourceLocation>,
+    message: String,
+    data: Vec<JsValuePreview>,
+) -> ConsoleEntry {
+    let mut args = vec![JsValuePreview::String { value: message }];
+    args.extend(data);
+    ConsoleEntry::new(level, location, args)
+}
+
+/// console 메서드를 호출한 사용자 코드의 호출 스택 (bootstrap.js 프레임 제외)
+fn caller_stack(scope: &mut v8::HandleScope) -> Vec<StackFrame> {
+    let Some(stack) = v8::StackTrace::current_stack_trace(scope, MAX_STACK_FRAMES) else {
+        return Vec::new();
+    };
+    let mut frames = Vec::new();
+    for index in 0..stack.get_frame_count() {
+        let Some(frame) = stack.get_frame(scope, index) else {
+            continue;
+        };
+        let file = frame
+            .get_script_name(scope)
+            .map(|name| name.to_rust_string_lossy(scope))
+            .unwrap_or_default();
+        if file == BOOTSTRAP_SCRIPT_NAME {
+            continue;
+        }
+        let function_name = frame
+            .get_function_name(scope)
+            .map(|name| name.to_rust_string_lossy(scope))
+            .filter(|name| !name.is_empty());
+        frames.push(StackFrame {
+            function_name,
+            location: SourceLocation {
+                file,
+                line: frame.get_line_number(),
+                column: frame.get_column(),
+            },
+        });
+    }
+    frames
+}
+
+/// console 메서드를 호출한 사용자 코드 위치
+fn caller_location(scope: &mut v8::HandleScope) -> Option<SourceLocation> {
+    caller_stack(scope)
+        .into_iter()
+        .next()
+        .map(|frame| frame.location)
+}
+
+/// JavaScript 배열의 각 요소를 미리보기로 변환
+fn previews<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    array: v8::Local<'s, v8::Array>,
+) -> Vec<JsValuePreview> {
+    let mut previews = Vec::with_capacity(array.length() as usize);
+    for index in 0..array.length() {
+        if let Some(value) = array.get_index(scope, index) {
+            previews.push(JsValuePreview::from_v8(scope, value));
+        }
+    }
+    previews
+}
+
+/// console.log, info, warn, error, debug
+#[op2(fast)]
+pub(crate) fn op_console_log<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    state: &mut OpState,
+    #[string] level: String,
+    args: v8::Local<'s, v8::Array>,
+) -> Result<(), AnyError> {
+    let location = caller_location(scope);
+    let args = previews(scope, args);
+    emit(
+        state,
+        ConsoleEntry::new(LogLevel::from_name(&level), location, args),
+    );
+    Ok(())
+}
+
+/// console.group, console.groupCollapsed
+#[op2(fast)]
+pub(crate) fn op_console_group<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    state: &mut OpState,
+    args: v8::Local<'s, v8::Array>,
+    collapsed: bool,
+) -> Result<(), AnyError> {
+    let location = caller_location(scope);
+    let args = previews(scope, args);
+    emit(
+        state,
+        ConsoleEntry::new(LogLevel::Log, location, args)
+            .with_kind(ConsoleEntryKind::GroupStart { collapsed }),
+    );
+    state.borrow_mut::<ConsoleState>().group_depth += 1;
+    Ok(())
+}
+
+/// console.groupEnd
+#[op2(fast)]
+pub(crate) fn op_console_group_end(state: &mut OpState) -> Result<(), AnyError> {
+    let console = state.borrow_mut::<ConsoleState>();
+    console.group_depth = console.group_depth.saturating_sub(1);
+    emit(
+        state,
+        ConsoleEntry::new(LogLevel::Log, None, Vec::new()).with_kind(ConsoleEntryKind::GroupEnd),
+    );
+    Ok(())
+}
+
+/// console.count, console.countReset
+#[op2(fast)]
+pub(crate) fn op_console_count(
+    scope: &mut v8::HandleScope,
+    state: &mut OpState,
+    #[string] label: String,
+    reset: bool,
+) -> Result<(), AnyError> {
+    let location = caller_location(scope);
+    let counts = &mut state.borrow_mut::<ConsoleState>().counts;
+    let entry = if reset {
+        match counts.get_mut(&label) {
+            Some(count) => {
+                *count = 0;
+                return Ok(());
+            }
+            None => message_entry(
+                LogLevel::Warn,
+                location,
+                format!("Count for '{}' does not exist", label),
+                Vec::new(),
+            ),
+        }
+    } else {
+        let count = counts.entry(label.clone()).or_default();
+        *count += 1;
+        message_entry(
+            LogLevel::Log,
+            location,
+            format!("{}: {}", label, count),
+            Vec::new(),
+        )
+    };
+    emit(state, entry);
+    Ok(())
+}
+
+/// console.time
+#[op2(fast)]
+pub(crate) fn op_console_time(
+    scope: &mut v8::HandleScope,
+    state: &mut OpState,
+    #[string] label: String,
+) -> Result<(), AnyError> {
+    let timers = &mut state.borrow_mut::<ConsoleState>().timers;
+    if let Entry::Vacant(entry) = timers.entry(label.clone()) {
+        entry.insert(Instant::now());
+    } else {
+        let location = caller_location(scope);
+        let message = format!("Label '{}' already exists for console.time()", label);
+        emit(
+            state,
+            message_entry(LogLevel::Warn, location, message, Vec::new()),
+        );
+    }
+    Ok(())
+}
+
+/// console.timeLog, console.timeEnd (`end`가 true면 타이머 제거)
+#[op2(fast)]
+pub(crate) fn op_console_time_log<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    state: &mut OpState,
+    #[string] label: String,
+    data: v8::Local<'s, v8::Array>,
+    end: bool,
+) -> Result<(), AnyError> {
+    let location = caller_location(scope);
+    let timers = &mut state.borrow_mut::<ConsoleState>().timers;
+    let started = if end {
+        timers.remove(&label)
+    } else {
+        timers.get(&label).copied()
+    };
+
+    let entry = match started {
+        Some(started) => {
+            let elapsed = started.elapsed().as_secs_f64() * 1000.0;
+            let data = previews(scope, data);
+            message_entry(
+                LogLevel::Log,
+                location,
+                format!("{}: {}", label, format_elapsed(elapsed)),
+                data,
+            )
+        }
+        None => {
+            let method = if end { "timeEnd" } else { "timeLog" };
+            message_entry(
+                LogLevel::Warn,
+                location,
+                format!("No such label '{}' for console.{}()", label, method),
+                Vec::new(),
+            )
+        }
+    };
+    emit(state, entry);
+    Ok(())
+}
+
+/// console.table (표 데이터는 bootstrap.js에서 열 단위로 정리해서 넘김)
+///
+/// `rows`의 각 요소는 `[인덱스, 칸 배열]`이며, 칸 배열에서 비어 있는 자리는 값이 없는 칸입니다.
+#[op2(fast)]
+pub(crate) fn op_console_table<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    state: &mut OpState,
+    #[string] index_header: String,
+    columns: v8::Local<'s, v8::Array>,
+    rows: v8::Local<'s, v8::Array>,
+) -> Result<(), AnyError> {
+    let location = caller_location(scope);
+    let columns: Vec<String> = previews(scope, columns)
+        .into_iter()
+        .map(|column| match column {
+            JsValuePreview::String { value } => value,
+            other => other.to_string(),
+        })
+        .collect();
+
+    let mut table_rows = Vec::with_capacity(rows.length() as usize);
+    for index in 0..rows.length() {
+        let Some(row) = rows.get_index(scope, index) else {
+            continue;
+        };
+        let Ok(row) = v8::Local::<v8::Array>::try_from(row) else {
+            continue;
+        };
+        let (Some(row_index), Some(cells)) = (row.get_index(scope, 0), row.get_index(scope, 1))
+        else {
+            continue;
+        };
+        let row_index = row_index.to_rust_string_lossy(scope);
+        let Ok(cells) = v8::Local::<v8::Array>::try_from(cells) else {
+            continue;
+        };
+
+        let mut row_cells = Vec::with_capacity(columns.len());
+        for column in 0..columns.len() as u32 {
+            let cell = match cells.has_index(scope, column) {
+                Some(true) => cells
+                    .get_index(scope, column)
+                    .map(|value| JsValuePreview::from_v8(scope, value)),
+                _ => None,
+            };
+            row_cells.push(cell);
+        }
+        table_rows.push(ConsoleTableRow {
+            index: row_index,
+            cells: row_cells,
+        });
+    }
+
+    let table = ConsoleTable {
+        index_header,
+        columns,
+        rows: table_rows,
+    };
+    let message = StyledText::from(render_table(&table));
+    emit(
+        state,
+        ConsoleEntry::new(LogLevel::Log, location, Vec::new())
+            .with_message(message)
+            .with_kind(ConsoleEntryKind::Table { table }),
+    );
+    Ok(())
+}
+
+/// console.trace
+#[op2(fast)]
+pub(crate) fn op_console_trace<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    state: &mut OpState,
+    args: v8::Local<'s, v8::Array>,
+) -> Result<(), AnyError> {
+    let stack = caller_stack(scope);
+    let location = stack.first().map(|frame| frame.location.clone());
+    let entry = ConsoleEntry::new(LogLevel::Trace, location, previews(scope, args));
+
+    // Node처럼 "Trace: 메시지" 다음 줄부터 호출 스택 출력
+    let mut message = StyledText::from("Trace");
+    if !entry.message.is_empty() {
+        message.append(StyledText::from(": "));
+        message.append(StyledText::from(entry.spans.clone()));
+    }
+    for frame in &stack {
+        message.append(StyledText::from(format!("\n    {}", frame)));
+    }
+
+    emit(
+        state,
+        entry
+            .with_message(message)
+            .with_kind(ConsoleEntryKind::Trace { stack }),
+    );
+    Ok(())
+}
+
+/// console.dir (문자열도 따옴표로 감싸서 inspect)
+#[op2(fast)]
+pub(crate) fn op_console_dir<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    state: &mut OpState,
+    value: v8::Local<'s, v8::Value>,
+    depth: f64,
+) -> Result<(), AnyError> {
+    let location = caller_location(scope);
+    let value = JsValuePreview::from_v8(scope, value);
+    let options = InspectOptions {
+        // 미리보기는 일정 깊이까지만 담겨 있으므로 그 이상은 의미 없음
+        depth: depth.clamp(0.0, 16.0) as usize,
+        ..Default::default()
+    };
+    let message = inspect(&value, &options);
+    emit(
+        state,
+        ConsoleEntry::new(LogLevel::Log, location, vec![value]).with_message(message),
+    );
+    Ok(())
+}
+
+/// console.clear (출력 창을 비우는 것은 UI에 맡김)
+#[op2(fast)]
+pub(crate) fn op_console_clear(state: &mut OpState) -> Result<(), AnyError> {
+    state.borrow_mut::<ConsoleState>().group_depth = 0;
+    emit(
+        state,
+        ConsoleEntry::new(LogLevel::Log, None, Vec::new()).with_kind(ConsoleEntryKind::Clear),
+    );
+    Ok(())
+}
+
+/// 경과 시간 표기 (Node의 console.timeEnd와 같은 형식)
+fn format_elapsed(ms: f64) -> String {
+    const SECOND: f64 = 1000.0;
+    const MINUTE: f64 = 60.0 * SECOND;
+    const HOUR: f64 = 60.0 * MINUTE;
+
+    if ms >= MINUTE {
+        let hours = (ms / HOUR).floor();
+        let minutes = ((ms % HOUR) / MINUTE).floor();
+        let seconds = (ms % MINUTE) / SECOND;
+        let seconds = format!("{:06.3}", seconds);
+        return if hours > 0.0 {
+            format!("{}:{:02}:{} (h:mm:ss.mmm)", hours, minutes, seconds)
+        } else {
+            format!("{}:{} (m:ss.mmm)", minutes, seconds)
+        };
+    }
+    if ms >= SECOND {
+        return format!("{:.3}s", ms / SECOND);
+    }
+    format!("{}ms", number_to_string((ms * 1000.0).round() / 1000.0))
+}
+
+/// 표의 칸 표시 (Node처럼 배열은 3개까지, 키가 세 개 이상인 객체는 접어서 표시)
+fn format_cell(value: &JsValuePreview) -> String {
+    let options = InspectOptions {
+        depth: 0,
+        break_length: usize::MAX,
+    };
+    match value {
+        JsValuePreview::Object {
+            class_name,
+            properties,
+            more,
+        } if properties.len() + more > 2 => {
+            let name = if class_name.is_empty() {
+                "Object"
+            } else {
+                class_name
+            };
+            format!("[{}]", name)
+        }
+        JsValuePreview::Array { items, length } if items.len() > 3 => {
+            let truncated = JsValuePreview::Array {
+                items: items[..3].to_vec(),
+                length: *length,
+            };
+            inspect(&truncated, &options).text()
+        }
+        value => inspect(value, &options).text(),
+    }
+}
+
+/// 화면에 표시되는 폭 (한글 등 전각 문자는 두 칸)
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x1100..=0x115F
+            | 0x2E80..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x1F900..=0x1F9FF
+            | 0x20000..=0x3FFFD => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// 텍스트 출력용 표 (Node의 console.table과 같은 상자 문자 사용)
+pub(crate) fn render_table(table: &ConsoleTable) -> String {
+    let mut header = vec![table.index_header.clone()];
+    header.extend(table.columns.iter().cloned());
+    let rows: Vec<Vec<String>> = table
+        .rows
+        .iter()
+        .map(|row| {
+            let mut cells = vec![row.index.clone()];
+            cells.extend(
+                row.cells
+                    .iter()
+                    .map(|cell| cell.as_ref().map(format_cell).unwrap_or_default()),
+            );
+            cells
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            std::iter::once(&header)
+                .chain(&rows)
+                .map(|row| row.get(column).map_or(0, |cell| display_width(cell)))
+                .max()
+                .unwrap_or(0)
+                + 2
+        })
+        .collect();
+
+    let divider = |left: &str, middle: &str, right: &str| {
+        let lines: Vec<String> = widths.iter().map(|width| "─".repeat(*width)).collect();
+        format!("{}{}{}", left, lines.join(middle), right)
+    };
+    let render_row = |row: &[String]| {
+        let cells: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(column, width)| {
+                let cell = row.get(column).map(String::as_str).unwrap_or_default();
+                format!(" {}{} ", cell, " ".repeat(width - 2 - display_width(cell)))
+            })
+            .collect();
+        format!("│{}│", cells.join("│"))
+    };
+
+    let mut lines = vec![
+        divider("┌", "┬", "┐"),
+        render_row(&header),
+        divider("├", "┼", "┤"),
+    ];
+    lines.extend(rows.iter().map(|row| render_row(row)));
+    lines.push(divider("└", "┴", "┘"));
+    lines.join("\n")
+}
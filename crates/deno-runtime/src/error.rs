@@ -0,0 +1,198 @@
+use crate::{ExecutionOutput, SourceLocation, StackFrame, BOOTSTRAP_SCRIPT_NAME};
+use deno_ast::diagnostics::Diagnostic;
+use deno_ast::ParseDiagnostic;
+use deno_core::error::JsError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// 스크립트 실행 중단/실패 사유
+///
+/// `anyhow::Error`로 감싸 반환되므로 호출 측에서는
+/// `error.downcast_ref::<ExecutionError>()`로 구분할 수 있습니다.
+#[derive(Debug, thiserror::Error)]
+pub enum ExecutionError {
+    /// 제한 시간 초과로 중단됨
+    #[error("실행 시간이 초과되었습니다 ({}ms)", .0.as_millis())]
+    TimedOut(Duration),
+
+    /// CancelHandle로 취소됨
+    #[error("실행이 취소되었습니다")]
+    Cancelled,
+
+    /// 힙 한도(bytes)에 도달해 중단됨
+    #[error("메모리 한도를 초과했습니다 ({} MB)", .0 / (1024 * 1024))]
+    OutOfMemory(usize),
+
+    /// 스크립트에서 잡히지 않은 예외 (문법 오류 포함)
+    #[error("{0}")]
+    Exception(ScriptError),
+}
+
+/// 실행이 실패하기 전까지 수집한 콘솔 출력
+///
+/// 실패한 실행의 오류에 context로 붙으므로 `error.downcast_ref::<PartialOutput>()`로
+/// 꺼낼 수 있습니다. 메시지는 원래 오류와 같고, `ExecutionError`도 그대로 downcast됩니다.
+#[derive(Debug, Clone)]
+pub struct PartialOutput {
+    pub output: ExecutionOutput,
+    message: String,
+}
+
+impl PartialOutput {
+    /// `error`에 실패 전까지의 출력을 붙임
+    pub(crate) fn attach(error: anyhow::Error, output: ExecutionOutput) -> anyhow::Error {
+        let message = error.to_string();
+        error.context(Self { output, message })
+    }
+}
+
+impl fmt::Display for PartialOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// 스크립트에서 던진 예외
+///
+/// deno_core의 `JsError`에서 만들며, 에디터가 실패한 줄에 밑줄을 긋고
+/// 바로 이동할 수 있도록 사용자 코드 안의 위치와 그 줄의 소스를 함께 담습니다.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptError {
+    /// 에러 클래스 이름 (`TypeError` 등, Error가 아닌 값을 던졌으면 None)
+    pub name: Option<String>,
+    pub message: String,
+    /// 호출 스택 (안쪽 프레임부터, bootstrap과 네이티브 프레임 제외)
+    pub frames: Vec<StackFrame>,
+    /// 사용자 코드에서 예외가 발생한 위치 (1부터 시작)
+    pub location: Option<SourceLocation>,
+    /// `location`에 해당하는 소스 줄
+    pub source_line: Option<String>,
+    /// `error.cause`로 연결된 원인
+    pub cause: Option<Box<ScriptError>>,
+}
+
+impl ScriptError {
+    /// `is_user_file`로 사용자 코드 프레임을 골라 `code`에서 해당 줄을 찾음
+    pub(crate) fn from_js_error(
+        error: &JsError,
+        code: &str,
+        is_user_file: &dyn Fn(&str) -> bool,
+    ) -> Self {
+        let frames: Vec<StackFrame> = error
+            .frames
+            .iter()
+            .filter_map(|frame| {
+                let file = frame.file_name.clone()?;
+                if file == BOOTSTRAP_SCRIPT_NAME {
+                    return None;
+                }
+                Some(StackFrame {
+                    function_name: frame.function_name.clone().filter(|name| !name.is_empty()),
+                    location: SourceLocation {
+                        file,
+                        line: frame.line_number? as usize,
+                        column: frame.column_number? as usize,
+                    },
+                })
+            })
+            .collect();
+
+        let location = frames
+            .iter()
+            .find(|frame| is_user_file(&frame.location.file))
+            .map(|frame| frame.location.clone());
+        let message = error.message.clone().unwrap_or_else(|| {
+            // Error가 아닌 값은 "Uncaught 값" 형태의 메시지만 있음
+            let message = &error.exception_message;
+            message
+                .strip_prefix("Uncaught ")
+                .unwrap_or(message)
+                .to_string()
+        });
+
+        let mut script_error = Self {
+            name: error.name.clone(),
+            message,
+            frames,
+            location: None,
+            source_line: None,
+            cause: error
+                .cause
+                .as_deref()
+                .map(|cause| Box::new(Self::from_js_error(cause, code, is_user_file))),
+        };
+        if let Some(location) = location {
+            script_error.set_location(location, code);
+        }
+        script_error
+    }
+
+    /// 실행 전 변환(TypeScript 타입 제거 등) 중 발견한 문법 오류
+    pub(crate) fn from_parse_diagnostic(
+        diagnostic: &ParseDiagnostic,
+        code: &str,
+        is_user_file: &dyn Fn(&str) -> bool,
+    ) -> Self {
+        let position = diagnostic.display_position();
+        let location = SourceLocation {
+            file: diagnostic.specifier().to_string(),
+            line: position.line_number,
+            column: position.column_number,
+        };
+
+        let mut script_error = Self {
+            name: Some("SyntaxError".to_string()),
+            message: diagnostic.message().to_string(),
+            frames: vec![StackFrame {
+                function_name: None,
+                location: location.clone(),
+            }],
+            location: None,
+            source_line: None,
+            cause: None,
+        };
+        if is_user_file(&location.file) {
+            script_error.set_location(location, code);
+        }
+        script_error
+    }
+
+    /// 예외 위치를 지정하고 `code`에서 해당 줄을 읽음
+    pub(crate) fn set_location(&mut self, location: SourceLocation, code: &str) {
+        self.source_line = location
+            .line
+            .checked_sub(1)
+            .and_then(|index| code.lines().nth(index))
+            .map(|line| line.trim_end_matches('\r').to_string());
+        self.location = Some(location);
+    }
+
+    /// `TypeError: 메시지` 형태의 첫 줄
+    fn header(&self) -> String {
+        match &self.name {
+            Some(name) if self.message.is_empty() => name.clone(),
+            Some(name) => format!("{}: {}", name, self.message),
+            None => self.message.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ScriptError {
+    /// deno처럼 `Uncaught` 뒤에 호출 스택과 원인을 이어서 출력
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Uncaught {}", self.header())?;
+        let mut error = self;
+        loop {
+            for frame in &error.frames {
+                write!(f, "\n    {}", frame)?;
+            }
+            let Some(cause) = &error.cause else {
+                return Ok(());
+            };
+            write!(f, "\nCaused by: {}", cause.header())?;
+            error = cause;
+        }
+    }
+}
@@ -0,0 +1,1007 @@
+use crate::{JsValuePreview, PromiseState};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 한 줄로 합칠 수 있는 안쪽 중첩 단계 수 (Node의 `compact: 3`)
+const COMPACT: usize = 3;
+
+/// 이보다 짧은 문자열은 줄바꿈이 있어도 나누지 않음
+const MIN_LINE_WIDTH: usize = 16;
+
+/// `util.inspect` 옵션
+#[derive(Debug, Clone, Copy)]
+pub struct InspectOptions {
+    /// 펼칠 최대 깊이 (이보다 깊은 객체는 `[Object]`로 표시)
+    pub depth: usize,
+    /// 한 줄에 출력할 최대 길이
+    pub break_length: usize,
+}
+
+impl Default for InspectOptions {
+    fn default() -> Self {
+        Self {
+            depth: 2,
+            break_length: 80,
+        }
+    }
+}
+
+/// 출력 조각의 스타일 (Node의 `util.inspect.styles`와 같은 분류)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpanStyle {
+    /// 함수, `[Circular *1]`, `[Object]` 등
+    Special,
+    Number,
+    Bigint,
+    Boolean,
+    Undefined,
+    Null,
+    String,
+    Symbol,
+    Date,
+    Regexp,
+}
+
+/// 스타일이 적용된 출력 조각
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StyledSpan {
+    pub text: String,
+    pub style: Option<SpanStyle>,
+    /// `%c`로 지정된 CSS
+    pub css: Option<String>,
+}
+
+/// 스타일 조각으로 이루어진 문자열
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StyledText {
+    spans: Vec<StyledSpan>,
+}
+
+impl StyledText {
+    fn plain(text: impl Into<String>) -> Self {
+        let mut styled = Self::default();
+        styled.push(text, None);
+        styled
+    }
+
+    fn styled(text: impl Into<String>, style: SpanStyle) -> Self {
+        let mut styled = Self::default();
+        styled.push(text, Some(style));
+        styled
+    }
+
+    fn push(&mut self, text: impl Into<String>, style: Option<SpanStyle>) {
+        self.push_span(StyledSpan {
+            text: text.into(),
+            style,
+            css: None,
+        });
+    }
+
+    fn push_str(&mut self, text: &str) {
+        self.push(text, None);
+    }
+
+    /// 스타일과 CSS가 같은 인접 조각은 하나로 합침
+    fn push_span(&mut self, span: StyledSpan) {
+        if span.text.is_empty() {
+            return;
+        }
+        match self.spans.last_mut() {
+            Some(last) if last.style == span.style && last.css == span.css => {
+                last.text.push_str(&span.text);
+            }
+            _ => self.spans.push(span),
+        }
+    }
+
+    pub(crate) fn append(&mut self, other: StyledText) {
+        for span in other.spans {
+            self.push_span(span);
+        }
+    }
+
+    /// 모든 조각에 CSS 적용 (이미 지정된 조각은 유지)
+    fn with_css(mut self, css: Option<&str>) -> Self {
+        if let Some(css) = css {
+            for span in &mut self.spans {
+                span.css.get_or_insert_with(|| css.to_string());
+            }
+        }
+        self
+    }
+
+    /// 표시 길이 (문자 수)
+    fn len(&self) -> usize {
+        self.spans
+            .iter()
+            .map(|span| span.text.chars().count())
+            .sum()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    fn contains_newline(&self) -> bool {
+        self.spans.iter().any(|span| span.text.contains('\n'))
+    }
+
+    /// 스타일 없이 이어 붙인 문자열
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    pub fn spans(&self) -> &[StyledSpan] {
+        &self.spans
+    }
+
+    pub fn into_spans(self) -> Vec<StyledSpan> {
+        self.spans
+    }
+}
+
+impl From<&str> for StyledText {
+    fn from(text: &str) -> Self {
+        Self::plain(text)
+    }
+}
+
+impl From<String> for StyledText {
+    fn from(text: String) -> Self {
+        Self::plain(text)
+    }
+}
+
+impl From<Vec<StyledSpan>> for StyledText {
+    fn from(spans: Vec<StyledSpan>) -> Self {
+        Self { spans }
+    }
+}
+
+fn join(items: Vec<StyledText>, separator: &str) -> StyledText {
+    let mut joined = StyledText::default();
+    for (index, item) in items.into_iter().enumerate() {
+        if index > 0 {
+            joined.push_str(separator);
+        }
+        joined.append(item);
+    }
+    joined
+}
+
+/// 값을 Node의 `util.inspect`와 같은 형식으로 변환
+pub fn inspect(value: &JsValuePreview, options: &InspectOptions) -> StyledText {
+    let mut circular_targets = Vec::new();
+    collect_circular_targets(value, &mut Vec::new(), &mut circular_targets);
+
+    let mut inspector = Inspector {
+        options: *options,
+        circular_targets,
+        path: Vec::new(),
+        indentation: 0,
+        current_depth: 0,
+    };
+    inspector.format_value(value, 0)
+}
+
+/// console 메서드 인자를 Node의 `util.format`과 같은 규칙으로 이어 붙임
+///
+/// 첫 인자가 문자열이면 `%s %d %i %f %j %o %O %c %%` 지정자를 처리하고,
+/// 남은 인자는 공백으로 구분하여 inspect한 결과를 붙입니다.
+pub fn format_args(args: &[JsValuePreview]) -> StyledText {
+    let mut output = StyledText::default();
+    let mut index = 0;
+    let mut join = "";
+
+    if let Some(JsValuePreview::String { value: first }) = args.first() {
+        if args.len() == 1 {
+            return StyledText::plain(first.as_str());
+        }
+
+        let chars: Vec<char> = first.chars().collect();
+        let mut css: Option<String> = None;
+        let mut last_pos = 0;
+        let mut i = 0;
+        while i + 1 < chars.len() {
+            if chars[i] != '%' {
+                i += 1;
+                continue;
+            }
+            i += 1;
+            let next = chars[i];
+            if index + 1 != args.len() {
+                let substitution = match next {
+                    's' | 'j' | 'd' | 'O' | 'o' | 'i' | 'f' | 'c' => {
+                        index += 1;
+                        let arg = &args[index];
+                        match next {
+                            's' => format_string_specifier(arg),
+                            'j' => StyledText::plain(to_json(arg)),
+                            'd' => StyledText::plain(format_number_specifier(arg, to_number)),
+                            'i' => StyledText::plain(format_number_specifier(arg, parse_int)),
+                            'f' => StyledText::plain(format_number_specifier(arg, parse_float)),
+                            'O' => inspect(arg, &InspectOptions::default()),
+                            'o' => inspect(
+                                arg,
+                                &InspectOptions {
+                                    depth: 4,
+                                    ..Default::default()
+                                },
+                            ),
+                            _ => {
+                                // %c: 이후 텍스트에 CSS 적용
+                                output.append(
+                                    StyledText::plain(slice(&chars, last_pos, i - 1))
+                                        .with_css(css.as_deref()),
+                                );
+                                css = match arg {
+                                    JsValuePreview::String { value } if !value.is_empty() => {
+                                        Some(value.clone())
+                                    }
+                                    _ => None,
+                                };
+                                last_pos = i + 1;
+                                i += 1;
+                                continue;
+                            }
+                        }
+                    }
+                    '%' => {
+                        output.append(
+                            StyledText::plain(slice(&chars, last_pos, i)).with_css(css.as_deref()),
+                        );
+                        last_pos = i + 1;
+                        i += 1;
+                        continue;
+                    }
+                    // 지정자가 아닌 문자는 그대로 둠
+                    _ => {
+                        i += 1;
+                        continue;
+                    }
+                };
+                if last_pos != i - 1 {
+                    output.append(
+                        StyledText::plain(slice(&chars, last_pos, i - 1)).with_css(css.as_deref()),
+                    );
+                }
+                output.append(substitution.with_css(css.as_deref()));
+                last_pos = i + 1;
+            } else if next == '%' {
+                output
+                    .append(StyledText::plain(slice(&chars, last_pos, i)).with_css(css.as_deref()));
+                last_pos = i + 1;
+            }
+            i += 1;
+        }
+
+        if last_pos != 0 {
+            index += 1;
+            join = " ";
+            if last_pos < chars.len() {
+                output.append(
+                    StyledText::plain(slice(&chars, last_pos, chars.len()))
+                        .with_css(css.as_deref()),
+                );
+            }
+        }
+    }
+
+    while index < args.len() {
+        output.push_str(join);
+        match &args[index] {
+            JsValuePreview::String { value } => output.push_str(value),
+            value => output.append(inspect(value, &InspectOptions::default())),
+        }
+        join = " ";
+        index += 1;
+    }
+
+    output
+}
+
+fn slice(chars: &[char], start: usize, end: usize) -> String {
+    chars[start..end].iter().collect()
+}
+
+/// `%s`: 원시값은 문자열로, 객체는 깊이 0으로 inspect
+fn format_string_specifier(arg: &JsValuePreview) -> StyledText {
+    match arg {
+        JsValuePreview::String { value } => StyledText::plain(value.as_str()),
+        JsValuePreview::Number { description }
+        | JsValuePreview::BigInt { description }
+        | JsValuePreview::Symbol { description } => StyledText::plain(description.as_str()),
+        JsValuePreview::Undefined => StyledText::plain("undefined"),
+        JsValuePreview::Null => StyledText::plain("null"),
+        JsValuePreview::Boolean { value } => StyledText::plain(value.to_string()),
+        other => StyledText::plain(
+            inspect(
+                other,
+                &InspectOptions {
+                    depth: 0,
+                    ..Default::default()
+                },
+            )
+            .text(),
+        ),
+    }
+}
+
+/// `%d`, `%i`, `%f`: 숫자로 변환 (BigInt는 그대로, 변환 불가는 NaN)
+fn format_number_specifier(arg: &JsValuePreview, convert: fn(&str) -> f64) -> String {
+    match arg {
+        JsValuePreview::BigInt { description } => description.clone(),
+        JsValuePreview::Number { description } => number_to_string(convert(description)),
+        JsValuePreview::String { value } => number_to_string(convert(value)),
+        JsValuePreview::Boolean { value } => {
+            number_to_string(convert(if *value { "1" } else { "0" }))
+        }
+        JsValuePreview::Null => number_to_string(convert("0")),
+        _ => "NaN".to_string(),
+    }
+}
+
+/// `Number(value)`와 같은 변환
+fn to_number(text: &str) -> f64 {
+    let text = text.trim();
+    if text.is_empty() {
+        return 0.0;
+    }
+    match text {
+        "Infinity" | "+Infinity" => f64::INFINITY,
+        "-Infinity" => f64::NEG_INFINITY,
+        _ => {
+            if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+                return i64::from_str_radix(hex, 16)
+                    .map(|n| n as f64)
+                    .unwrap_or(f64::NAN);
+            }
+            // Rust는 "inf", "nan" 등도 허용하므로 숫자 문자만 통과
+            if text
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'))
+            {
+                text.parse().unwrap_or(f64::NAN)
+            } else {
+                f64::NAN
+            }
+        }
+    }
+}
+
+/// `parseInt(value)`와 같은 변환 (앞부분의 정수만 사용)
+fn parse_int(text: &str) -> f64 {
+    let text = text.trim_start();
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let integer: String = digits.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if integer.is_empty() {
+        return f64::NAN;
+    }
+    sign * integer.parse::<f64>().unwrap_or(f64::NAN)
+}
+
+/// `parseFloat(value)`와 같은 변환 (앞부분의 실수만 사용)
+fn parse_float(text: &str) -> f64 {
+    let text = text.trim_start();
+    let unsigned = text.trim_start_matches(['+', '-']);
+    if unsigned.starts_with("Infinity") {
+        return if text.starts_with('-') {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        };
+    }
+    // 파싱 가능한 가장 긴 접두사 사용
+    (1..=text.len())
+        .rev()
+        .filter(|&end| text.is_char_boundary(end))
+        .find_map(|end| {
+            let candidate = &text[..end];
+            let valid = candidate
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'));
+            if valid {
+                candidate.parse::<f64>().ok()
+            } else {
+                None
+            }
+        })
+        .unwrap_or(f64::NAN)
+}
+
+/// JavaScript의 `String(number)`와 같은 표기
+pub(crate) fn number_to_string(number: f64) -> String {
+    if number.is_nan() {
+        return "NaN".to_string();
+    }
+    if number.is_infinite() {
+        return if number > 0.0 {
+            "Infinity"
+        } else {
+            "-Infinity"
+        }
+        .to_string();
+    }
+    if number == 0.0 {
+        return if number.is_sign_negative() { "-0" } else { "0" }.to_string();
+    }
+
+    let abs = number.abs();
+    if (1e-6..1e21).contains(&abs) {
+        return number.to_string();
+    }
+
+    // 지수 표기: Rust는 "1e21", JavaScript는 "1e+21"
+    let exponential = format!("{:e}", number);
+    match exponential.split_once('e') {
+        Some((mantissa, exponent)) if !exponent.starts_with('-') => {
+            format!("{}e+{}", mantissa, exponent)
+        }
+        _ => exponential,
+    }
+}
+
+/// `%j`: JSON.stringify와 같은 변환 (순환 참조는 `[Circular]`)
+fn to_json(value: &JsValuePreview) -> String {
+    json_value(value).unwrap_or_else(|| "undefined".to_string())
+}
+
+fn json_value(value: &JsValuePreview) -> Option<String> {
+    let json = match value {
+        JsValuePreview::Undefined
+        | JsValuePreview::Function { .. }
+        | JsValuePreview::Symbol { .. } => return None,
+        JsValuePreview::Null => "null".to_string(),
+        JsValuePreview::Boolean { value } => value.to_string(),
+        JsValuePreview::Number { description } => match description.as_str() {
+            "NaN" | "Infinity" | "-Infinity" => "null".to_string(),
+            "-0" => "0".to_string(),
+            _ => description.clone(),
+        },
+        JsValuePreview::BigInt { description } => description.trim_end_matches('n').to_string(),
+        JsValuePreview::String { value } => serde_json::Value::from(value.as_str()).to_string(),
+        JsValuePreview::Date { iso } => serde_json::Value::from(iso.as_str()).to_string(),
+        JsValuePreview::Array { items, .. } => {
+            let items: Vec<String> = items
+                .iter()
+                .map(|item| json_value(item).unwrap_or_else(|| "null".to_string()))
+                .collect();
+            format!("[{}]", items.join(","))
+        }
+        JsValuePreview::Object { properties, .. } => {
+            let properties: Vec<String> = properties
+                .iter()
+                .filter_map(|property| {
+                    let value = json_value(&property.value)?;
+                    Some(format!(
+                        "{}:{}",
+                        serde_json::Value::from(property.key.as_str()),
+                        value
+                    ))
+                })
+                .collect();
+            format!("{{{}}}", properties.join(","))
+        }
+        JsValuePreview::Error { .. }
+        | JsValuePreview::Map { .. }
+        | JsValuePreview::Set { .. }
+        | JsValuePreview::RegExp { .. }
+        | JsValuePreview::Promise { .. } => "{}".to_string(),
+        JsValuePreview::Circular { .. } => "\"[Circular]\"".to_string(),
+        JsValuePreview::Truncated { .. } => "{}".to_string(),
+    };
+    Some(json)
+}
+
+/// 순환 참조 대상 객체를 발견 순서대로 수집 (`<ref *1>` 번호 부여용)
+fn collect_circular_targets<'a>(
+    value: &'a JsValuePreview,
+    path: &mut Vec<&'a JsValuePreview>,
+    targets: &mut Vec<*const JsValuePreview>,
+) {
+    if let JsValuePreview::Circular { depth } = value {
+        if let Some(target) = path.get(*depth) {
+            let target = *target as *const JsValuePreview;
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        return;
+    }
+
+    let children: Vec<&JsValuePreview> = match value {
+        JsValuePreview::Array { items, .. } | JsValuePreview::Set { items, .. } => {
+            items.iter().collect()
+        }
+        JsValuePreview::Object { properties, .. } => {
+            properties.iter().map(|property| &property.value).collect()
+        }
+        JsValuePreview::Map { entries, .. } => entries
+            .iter()
+            .flat_map(|entry| [&entry.key, &entry.value])
+            .collect(),
+        JsValuePreview::Promise { value, .. } => value.iter().map(|value| &**value).collect(),
+        _ => return,
+    };
+
+    path.push(value);
+    for child in children {
+        collect_circular_targets(child, path, targets);
+    }
+    path.pop();
+}
+
+struct Inspector<'a> {
+    options: InspectOptions,
+    circular_targets: Vec<*const JsValuePreview>,
+    /// 현재 펼치고 있는 조상 객체들 (Circular의 depth로 대상을 찾음)
+    path: Vec<&'a JsValuePreview>,
+    indentation: usize,
+    /// 가장 최근에 펼친 객체의 깊이 (한 줄 출력 여부 판단용)
+    current_depth: usize,
+}
+
+impl<'a> Inspector<'a> {
+    fn format_value(&mut self, value: &'a JsValuePreview, level: usize) -> StyledText {
+        match value {
+            JsValuePreview::Undefined => StyledText::styled("undefined", SpanStyle::Undefined),
+            JsValuePreview::Null => StyledText::styled("null", SpanStyle::Null),
+            JsValuePreview::Boolean { value } => {
+                StyledText::styled(value.to_string(), SpanStyle::Boolean)
+            }
+            JsValuePreview::Number { description } => {
+                StyledText::styled(description.as_str(), SpanStyle::Number)
+            }
+            JsValuePreview::BigInt { description } => {
+                StyledText::styled(description.as_str(), SpanStyle::Bigint)
+            }
+            JsValuePreview::Symbol { description } => {
+                StyledText::styled(description.as_str(), SpanStyle::Symbol)
+            }
+            JsValuePreview::String { value } => self.format_string(value),
+            JsValuePreview::Function { name, is_class } => {
+                let kind = if *is_class { "class" } else { "Function" };
+                let text = match (name.is_empty(), is_class) {
+                    (true, _) => format!("[{} (anonymous)]", kind),
+                    (false, true) => format!("[class {}]", name),
+                    (false, false) => format!("[Function: {}]", name),
+                };
+                StyledText::styled(text, SpanStyle::Special)
+            }
+            JsValuePreview::Date { iso } => StyledText::styled(iso.as_str(), SpanStyle::Date),
+            JsValuePreview::RegExp { source } => {
+                StyledText::styled(source.as_str(), SpanStyle::Regexp)
+            }
+            JsValuePreview::Error {
+                name,
+                message,
+                stack,
+            } => {
+                let text = match stack {
+                    Some(stack) => stack.clone(),
+                    None if message.is_empty() => name.clone(),
+                    None => format!("{}: {}", name, message),
+                };
+                // 중첩된 에러는 스택의 각 줄을 현재 들여쓰기에 맞춤
+                let indentation = format!("\n{}", " ".repeat(self.indentation));
+                StyledText::plain(text.replace('\n', &indentation))
+            }
+            JsValuePreview::Circular { depth } => {
+                let index = self
+                    .path
+                    .get(*depth)
+                    .and_then(|target| self.reference_index(target))
+                    .map(|index| format!(" *{}", index))
+                    .unwrap_or_default();
+                StyledText::styled(format!("[Circular{}]", index), SpanStyle::Special)
+            }
+            JsValuePreview::Truncated { class_name } => {
+                StyledText::styled(format!("[{}]", class_name), SpanStyle::Special)
+            }
+            JsValuePreview::Array { .. }
+            | JsValuePreview::Object { .. }
+            | JsValuePreview::Map { .. }
+            | JsValuePreview::Set { .. }
+            | JsValuePreview::Promise { .. } => self.format_container(value, level),
+        }
+    }
+
+    /// 문자열 (객체 안에서는 따옴표로 감싸고, 긴 여러 줄 문자열은 줄 단위로 나눔)
+    fn format_string(&self, value: &str) -> StyledText {
+        let length = value.chars().count();
+        if length > MIN_LINE_WIDTH
+            && length + self.indentation + 4 > self.options.break_length
+            && value.trim_end_matches('\n').contains('\n')
+        {
+            let separator = format!(" +\n{}", " ".repeat(self.indentation + 2));
+            let lines = value
+                .split_inclusive('\n')
+                .map(|line| StyledText::styled(quote_string(line), SpanStyle::String))
+                .collect();
+            return join(lines, &separator);
+        }
+        StyledText::styled(quote_string(value), SpanStyle::String)
+    }
+
+    fn reference_index(&self, target: &JsValuePreview) -> Option<usize> {
+        let target = target as *const JsValuePreview;
+        self.circular_targets
+            .iter()
+            .position(|candidate| *candidate == target)
+            .map(|index| index + 1)
+    }
+
+    fn format_container(&mut self, value: &'a JsValuePreview, level: usize) -> StyledText {
+        if level > self.options.depth {
+            return StyledText::styled(format!("[{}]", container_name(value)), SpanStyle::Special);
+        }
+
+        self.current_depth = level;
+        self.path.push(value);
+        self.indentation += 2;
+
+        let mut is_array = false;
+        let mut has_more = false;
+        let mut all_numbers = false;
+        let (open, output) = match value {
+            JsValuePreview::Array { items, length } => {
+                is_array = true;
+                all_numbers = items.iter().all(|item| {
+                    matches!(
+                        item,
+                        JsValuePreview::Number { .. } | JsValuePreview::BigInt { .. }
+                    )
+                });
+                let mut output: Vec<StyledText> = items
+                    .iter()
+                    .map(|item| self.format_value(item, level + 1))
+                    .collect();
+                if *length > items.len() {
+                    has_more = true;
+                    output.push(more_items(*length - items.len(), "item"));
+                }
+                ("[".to_string(), output)
+            }
+            JsValuePreview::Object {
+                class_name,
+                properties,
+                more,
+            } => {
+                let mut output: Vec<StyledText> = properties
+                    .iter()
+                    .map(|property| {
+                        let mut entry = format_key(&property.key);
+                        entry.push_str(": ");
+                        entry.append(self.format_value(&property.value, level + 1));
+                        entry
+                    })
+                    .collect();
+                if *more > 0 {
+                    output.push(more_items(*more, "property"));
+                }
+                let open = if class_name == "Object" || class_name.is_empty() {
+                    "{".to_string()
+                } else {
+                    format!("{} {{", class_name)
+                };
+                (open, output)
+            }
+            JsValuePreview::Map { entries, size } => {
+                let mut output: Vec<StyledText> = entries
+                    .iter()
+                    .map(|entry| {
+                        let mut text = self.format_value(&entry.key, level + 1);
+                        text.push_str(" => ");
+                        text.append(self.format_value(&entry.value, level + 1));
+                        text
+                    })
+                    .collect();
+                if *size > entries.len() {
+                    output.push(more_items(*size - entries.len(), "item"));
+                }
+                (format!("Map({}) {{", size), output)
+            }
+            JsValuePreview::Set { items, size } => {
+                let mut output: Vec<StyledText> = items
+                    .iter()
+                    .map(|item| self.format_value(item, level + 1))
+                    .collect();
+                if *size > items.len() {
+                    output.push(more_items(*size - items.len(), "item"));
+                }
+                (format!("Set({}) {{", size), output)
+            }
+            JsValuePreview::Promise { state, value } => {
+                let output = match (state, value) {
+                    (PromiseState::Fulfilled, Some(value)) => {
+                        vec![self.format_value(value, level + 1)]
+                    }
+                    (PromiseState::Rejected, Some(value)) => {
+                        let mut text = StyledText::styled("<rejected>", SpanStyle::Special);
+                        text.push_str(" ");
+                        text.append(self.format_value(value, level + 1));
+                        vec![text]
+                    }
+                    _ => vec![StyledText::styled("<pending>", SpanStyle::Special)],
+                };
+                ("Promise {".to_string(), output)
+            }
+            _ => unreachable!("format_container는 컨테이너 값에만 호출됩니다"),
+        };
+
+        self.indentation -= 2;
+        self.path.pop();
+
+        let close = if is_array { "]" } else { "}" };
+        let base = self
+            .reference_index(value)
+            .map(|index| StyledText::styled(format!("<ref *{}>", index), SpanStyle::Special))
+            .unwrap_or_default();
+
+        if output.is_empty() {
+            let mut text = base;
+            if !text.is_empty() {
+                text.push_str(" ");
+            }
+            text.push_str(&open);
+            text.push_str(close);
+            return text;
+        }
+
+        self.reduce_to_single_string(
+            output,
+            base,
+            &open,
+            close,
+            level,
+            is_array.then_some((has_more, all_numbers)),
+        )
+    }
+
+    /// 항목을 한 줄 또는 여러 줄로 배치 (Node의 reduceToSingleString과 같은 규칙)
+    fn reduce_to_single_string(
+        &self,
+        output: Vec<StyledText>,
+        base: StyledText,
+        open: &str,
+        close: &str,
+        level: usize,
+        array: Option<(bool, bool)>,
+    ) -> StyledText {
+        let entries = output.len();
+        let output = match array {
+            Some((has_more, all_numbers)) if entries > 6 => {
+                self.group_array_elements(output, has_more, all_numbers)
+            }
+            _ => output,
+        };
+
+        let mut text = base;
+        if !text.is_empty() {
+            text.push_str(" ");
+        }
+        text.push_str(open);
+
+        if self.current_depth - level < COMPACT && entries == output.len() {
+            let start = output.len() + self.indentation + open.chars().count() + text.len() + 10;
+            if self.is_below_break_length(&output, start) {
+                let joined = join(output.clone(), ", ");
+                if !joined.contains_newline() {
+                    text.push_str(" ");
+                    text.append(joined);
+                    text.push_str(" ");
+                    text.push_str(close);
+                    return text;
+                }
+            }
+        }
+
+        let indentation = format!("\n{}", " ".repeat(self.indentation));
+        text.push_str(&indentation);
+        text.push_str("  ");
+        text.append(join(output, &format!(",{}  ", indentation)));
+        text.push_str(&indentation);
+        text.push_str(close);
+        text
+    }
+
+    fn is_below_break_length(&self, output: &[StyledText], start: usize) -> bool {
+        let mut total_length = output.len() + start;
+        if total_length + output.len() > self.options.break_length {
+            return false;
+        }
+        for entry in output {
+            total_length += entry.len();
+            if total_length > self.options.break_length {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// 짧은 항목이 많은 배열을 여러 열로 정렬 (Node의 groupArrayElements와 같은 규칙)
+    fn group_array_elements(
+        &self,
+        output: Vec<StyledText>,
+        has_more: bool,
+        all_numbers: bool,
+    ) -> Vec<StyledText> {
+        if output.iter().any(StyledText::contains_newline) {
+            return output;
+        }
+
+        const SEPARATOR_SPACE: usize = 2;
+        let output_length = if has_more {
+            output.len() - 1
+        } else {
+            output.len()
+        };
+        let data_len: Vec<usize> = output.iter().map(StyledText::len).collect();
+        let total_length: usize = data_len[..output_length]
+            .iter()
+            .map(|len| len + SEPARATOR_SPACE)
+            .sum();
+        let max_length = data_len[..output_length].iter().copied().max().unwrap_or(0);
+        let actual_max = max_length + SEPARATOR_SPACE;
+
+        if actual_max * 3 + self.indentation >= self.options.break_length
+            || (total_length as f64 / actual_max as f64 <= 5.0 && max_length > 6)
+        {
+            return output;
+        }
+
+        let approx_char_heights = 2.5;
+        let average_bias = (actual_max as f64 - total_length as f64 / output.len() as f64).sqrt();
+        let biased_max = (actual_max as f64 - 3.0 - average_bias).max(1.0);
+        let columns = [
+            ((approx_char_heights * biased_max * output_length as f64).sqrt() / biased_max).round()
+                as usize,
+            (self.options.break_length - self.indentation) / actual_max,
+            COMPACT * 4,
+            15,
+        ]
+        .into_iter()
+        .min()
+        .unwrap_or(1);
+        if columns <= 1 {
+            return output;
+        }
+
+        let max_line_length: Vec<usize> = (0..columns)
+            .map(|column| {
+                (column..output.len())
+                    .step_by(columns)
+                    .map(|index| data_len[index])
+                    .max()
+                    .unwrap_or(0)
+                    + SEPARATOR_SPACE
+            })
+            .collect();
+
+        let mut grouped = Vec::new();
+        let mut entries = output.into_iter();
+        for row_start in (0..output_length).step_by(columns) {
+            let row_end = (row_start + columns).min(output_length);
+            let mut row = StyledText::default();
+            for index in row_start..row_end {
+                let entry = entries.next().unwrap_or_default();
+                let is_last = index + 1 == row_end;
+                let width = max_line_length[index - row_start];
+                let padding = if is_last {
+                    if all_numbers {
+                        width - SEPARATOR_SPACE - data_len[index]
+                    } else {
+                        0
+                    }
+                } else {
+                    width - data_len[index] - SEPARATOR_SPACE
+                };
+
+                if all_numbers {
+                    // 숫자는 오른쪽 정렬
+                    row.push_str(&" ".repeat(padding));
+                    row.append(entry);
+                    if !is_last {
+                        row.push_str(", ");
+                    }
+                } else {
+                    row.append(entry);
+                    if !is_last {
+                        row.push_str(", ");
+                        row.push_str(&" ".repeat(padding));
+                    }
+                }
+            }
+            grouped.push(row);
+        }
+        if has_more {
+            grouped.extend(entries);
+        }
+        grouped
+    }
+}
+
+/// 깊이 제한으로 펼치지 않을 때 표시할 이름
+fn container_name(value: &JsValuePreview) -> String {
+    match value {
+        JsValuePreview::Array { .. } => "Array".to_string(),
+        JsValuePreview::Map { .. } => "Map".to_string(),
+        JsValuePreview::Set { .. } => "Set".to_string(),
+        JsValuePreview::Promise { .. } => "Promise".to_string(),
+        JsValuePreview::Object { class_name, .. } if !class_name.is_empty() => class_name.clone(),
+        _ => "Object".to_string(),
+    }
+}
+
+fn more_items(count: usize, noun: &str) -> StyledText {
+    let plural = match (count, noun) {
+        (1, _) => noun.to_string(),
+        (_, "property") => "properties".to_string(),
+        _ => format!("{}s", noun),
+    };
+    StyledText::plain(format!("... {} more {}", count, plural))
+}
+
+/// 식별자로 쓸 수 있는 키는 그대로, 아니면 따옴표로 감쌈
+fn format_key(key: &str) -> StyledText {
+    let mut chars = key.chars();
+    let is_identifier = chars
+        .next()
+        .map(|c| c.is_ascii_alphabetic() || c == '_')
+        .unwrap_or(false)
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_identifier {
+        StyledText::plain(key)
+    } else {
+        StyledText::styled(quote_string(key), SpanStyle::String)
+    }
+}
+
+/// Node와 같은 규칙으로 따옴표 선택 및 이스케이프 (기본은 작은따옴표)
+fn quote_string(value: &str) -> String {
+    let quote = if !value.contains('\'') {
+        '\''
+    } else if !value.contains('"') {
+        '"'
+    } else if !value.contains('`') && !value.contains("${") {
+        '`'
+    } else {
+        '\''
+    };
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push(quote);
+    for c in value.chars() {
+        match c {
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            '\u{8}' => quoted.push_str("\\b"),
+            '\u{c}' => quoted.push_str("\\f"),
+            '\u{b}' => quoted.push_str("\\v"),
+            '\\' => quoted.push_str("\\\\"),
+            c if c == quote => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            c if (c as u32) < 0x20 || c as u32 == 0x7f => {
+                quoted.push_str(&format!("\\x{:02X}", c as u32));
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push(quote);
+    quoted
+}
+
+impl fmt::Display for JsValuePreview {
+    /// `util.inspect` 기본 옵션으로 출력
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", inspect(self, &InspectOptions::default()).text())
+    }
+}
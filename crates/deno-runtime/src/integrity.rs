@@ -0,0 +1,234 @@
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+/// 검증한 무결성 값을 저장하는 파일 (`캐시/<이름>/<버전>/integrity`)
+pub(crate) const INTEGRITY_FILE: &str = "integrity";
+
+/// SRI 해시 알고리즘 (뒤에 있을수록 강함)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Algorithm {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl Algorithm {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "sha1" => Some(Self::Sha1),
+            "sha256" => Some(Self::Sha256),
+            "sha384" => Some(Self::Sha384),
+            "sha512" => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sha1 => "sha1",
+            Self::Sha256 => "sha256",
+            Self::Sha384 => "sha384",
+            Self::Sha512 => "sha512",
+        }
+    }
+
+    fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Sha1 => Sha1::digest(data).to_vec(),
+            Self::Sha256 => Sha256::digest(data).to_vec(),
+            Self::Sha384 => Sha384::digest(data).to_vec(),
+            Self::Sha512 => Sha512::digest(data).to_vec(),
+        }
+    }
+
+    /// 해시 길이 (bytes)
+    fn len(self) -> usize {
+        match self {
+            Self::Sha1 => 20,
+            Self::Sha256 => 32,
+            Self::Sha384 => 48,
+            Self::Sha512 => 64,
+        }
+    }
+
+    /// `sha512-<base64>` 형식의 SRI 값
+    fn sri(self, digest: &[u8]) -> String {
+        format!("{}-{}", self.name(), STANDARD.encode(digest))
+    }
+}
+
+/// 레지스트리가 게시한 tarball 해시 (`dist.integrity`의 SRI 값과 `dist.shasum`)
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Integrity {
+    hashes: Vec<(Algorithm, Vec<u8>)>,
+}
+
+impl Integrity {
+    /// 지원하지 않는 알고리즘이나 잘못된 값은 무시
+    ///
+    /// 게시한 값이 있는데 하나도 해석할 수 없으면 검증 없이 설치하지 않도록 오류를 반환합니다.
+    pub(crate) fn new(integrity: Option<&str>, shasum: Option<&str>) -> Result<Self> {
+        let mut hashes: Vec<(Algorithm, Vec<u8>)> = integrity
+            .into_iter()
+            .flat_map(str::split_whitespace)
+            .filter_map(parse_sri)
+            .collect();
+        if let Some(digest) = shasum.and_then(decode_hex) {
+            hashes.push((Algorithm::Sha1, digest));
+        }
+
+        let published = [integrity, shasum]
+            .into_iter()
+            .flatten()
+            .any(|value| !value.trim().is_empty());
+        if published && hashes.is_empty() {
+            anyhow::bail!(
+                "레지스트리가 게시한 tarball 해시를 해석할 수 없습니다 (integrity: {}, shasum: {})",
+                integrity.unwrap_or("없음"),
+                shasum.unwrap_or("없음")
+            );
+        }
+        Ok(Self { hashes })
+    }
+
+    /// 가장 강한 알고리즘으로 tarball 검증 후 검증한 SRI 값 반환
+    ///
+    /// 레지스트리가 해시를 게시하지 않았으면 검증 없이 sha512 값을 계산해서 반환합니다.
+    pub(crate) fn verify(&self, data: &[u8]) -> Result<String> {
+        let Some(algorithm) = self.hashes.iter().map(|(algorithm, _)| *algorithm).max() else {
+            return Ok(Algorithm::Sha512.sri(&Algorithm::Sha512.digest(data)));
+        };
+
+        let actual = algorithm.digest(data);
+        if self.hashes.iter().any(|(expected_algorithm, expected)| {
+            *expected_algorithm == algorithm && *expected == actual
+        }) {
+            return Ok(algorithm.sri(&actual));
+        }
+
+        let expected = self
+            .hashes
+            .iter()
+            .filter(|(expected_algorithm, _)| *expected_algorithm == algorithm)
+            .map(|(_, expected)| algorithm.sri(expected))
+            .collect::<Vec<_>>()
+            .join(" ");
+        anyhow::bail!(
+            "tarball 무결성 검증 실패 (손상되었거나 변조된 다운로드): 예상 {}, 실제 {}",
+            expected,
+            algorithm.sri(&actual)
+        )
+    }
+
+    /// 캐시에 저장된 SRI 값이 레지스트리가 게시한 해시와 같은지
+    ///
+    /// 게시한 해시가 없으면 설치할 때 검증하거나 계산해서 저장한 형식의 값인지 확인하므로,
+    /// 저장된 값이 없어졌거나 잘못 바뀐 캐시는 받아들이지 않습니다.
+    pub(crate) fn matches(&self, stored: &str) -> bool {
+        let Some((algorithm, digest)) = parse_sri(stored.trim()) else {
+            return false;
+        };
+        if self.hashes.is_empty() {
+            return true;
+        }
+        self.hashes.iter().any(|(expected_algorithm, expected)| {
+            *expected_algorithm == algorithm && *expected == digest
+        })
+    }
+}
+
+/// `sha512-<base64>?옵션` 형식의 SRI 값 하나 (해시 길이가 알고리즘과 다르면 무시)
+fn parse_sri(entry: &str) -> Option<(Algorithm, Vec<u8>)> {
+    let entry = entry.split('?').next()?;
+    let (algorithm, digest) = entry.split_once('-')?;
+    let algorithm = Algorithm::parse(algorithm)?;
+    let digest = STANDARD.decode(digest).ok()?;
+    (digest.len() == algorithm.len()).then_some((algorithm, digest))
+}
+
+/// `dist.shasum`의 16진수 sha1 값
+// `usize::is_multiple_of`는 Rust 1.87부터 사용할 수 있음
+#[allow(clippy::manual_is_multiple_of)]
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tarball_integrity() {
+        let data = b"executejs tarball";
+        let sha512 = "sha512-yw7+jehT37SRdPHPU0AC0SR/bMg6dLaWnaJpETwQC6/uQqe+Py8cGa3KksWLGBrMyy6H2EuK4j42Pi2rwcj/jA==";
+        let sha1 = "sha1-h8UQ+QvAoZy2FKu/+wV1tmpFqfA=";
+        let shasum = "87c510f90bc0a19cb614abbffb0575b66a45a9f0";
+
+        let integrity = Integrity::new(Some(sha512), Some(shasum)).unwrap();
+        assert_eq!(integrity.verify(data).unwrap(), sha512);
+        assert!(integrity.matches(sha512));
+        assert!(integrity.matches(sha1));
+        // 잘리거나 바뀐 tarball은 거부
+        let error = integrity.verify(b"executejs tarbal").unwrap_err();
+        assert!(error.to_string().contains("무결성 검증 실패"), "{}", error);
+        assert!(!integrity.matches("sha512-AAAA"));
+
+        // 가장 강한 알고리즘으로 검증 (sha1이 맞아도 sha512가 다르면 실패)
+        let tampered = Integrity::new(
+            Some(&format!("{} {}", sha1, Algorithm::Sha512.sri(&[0; 64]))),
+            None,
+        )
+        .unwrap();
+        assert!(tampered.verify(data).is_err());
+
+        // integrity가 없는 오래된 패키지는 shasum으로 검증
+        let legacy = Integrity::new(None, Some(&shasum.to_uppercase())).unwrap();
+        assert_eq!(legacy.verify(data).unwrap(), sha1);
+        assert!(Integrity::new(None, Some(&"0".repeat(40)))
+            .unwrap()
+            .verify(data)
+            .is_err());
+
+        // 해시가 없으면 검증 없이 sha512 값을 기록하고, 캐시는 기록한 값이 온전할 때만 사용
+        let unpublished = Integrity::new(None, None).unwrap();
+        assert_eq!(unpublished.verify(data).unwrap(), sha512);
+        assert!(unpublished.matches(sha512));
+        assert!(unpublished.matches(sha1));
+        assert!(!unpublished.matches(""));
+        assert!(!unpublished.matches("sha512-AAAA"));
+        assert!(!unpublished.matches("tampered"));
+    }
+
+    #[test]
+    fn test_unparseable_integrity() {
+        // 게시한 integrity를 해석할 수 없고 shasum도 없으면 검증 없이 설치하지 않음
+        for integrity in ["sha999-AAAA", "sha512-???", "md5-AAAA sha512"] {
+            let error = Integrity::new(Some(integrity), None).unwrap_err();
+            assert!(error.to_string().contains(integrity), "{}", error);
+        }
+        assert!(Integrity::new(None, Some("not-hex")).is_err());
+
+        // shasum으로 검증할 수 있거나 게시한 값이 비어 있으면 사용
+        let data = b"executejs tarball";
+        let integrity = Integrity::new(
+            Some("sha999-AAAA"),
+            Some("87c510f90bc0a19cb614abbffb0575b66a45a9f0"),
+        )
+        .unwrap();
+        assert_eq!(
+            integrity.verify(data).unwrap(),
+            "sha1-h8UQ+QvAoZy2FKu/+wV1tmpFqfA="
+        );
+        assert!(Integrity::new(Some(" "), None).is_ok());
+    }
+}
@@ -0,0 +1,136 @@
+use crate::ExecutionError;
+use anyhow::Result;
+use deno_core::v8;
+use std::future::Future;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// 실행 중단 사유
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum InterruptReason {
+    TimedOut(Duration),
+    Cancelled,
+    /// 힙 한도(bytes) 초과
+    OutOfMemory(usize),
+}
+
+impl InterruptReason {
+    fn into_error(self) -> ExecutionError {
+        match self {
+            InterruptReason::TimedOut(timeout) => ExecutionError::TimedOut(timeout),
+            InterruptReason::Cancelled => ExecutionError::Cancelled,
+            InterruptReason::OutOfMemory(heap_limit) => ExecutionError::OutOfMemory(heap_limit),
+        }
+    }
+}
+
+/// 실행 중인 isolate와 중단 사유를 공유하는 상태
+#[derive(Default)]
+struct InterruptState {
+    isolate: Mutex<Option<v8::IsolateHandle>>,
+    reason: Mutex<Option<InterruptReason>>,
+    notify: Notify,
+}
+
+/// 실행 중인 스크립트를 취소하는 핸들
+///
+/// 다른 스레드(예: Tauri 명령)에서 `cancel()`을 호출하면 V8의
+/// `terminate_execution`으로 동기 루프까지 중단하고, 이벤트 루프 대기도 깨웁니다.
+#[derive(Clone, Default)]
+pub struct CancelHandle {
+    state: Arc<InterruptState>,
+}
+
+impl CancelHandle {
+    /// 현재 실행 취소 (실행이 시작되기 전이면 다음 실행을 시작하자마자 중단)
+    pub fn cancel(&self) {
+        self.interrupt(InterruptReason::Cancelled);
+    }
+
+    /// 새 실행 시작: isolate 핸들을 등록하고, 시작 전에 취소됐으면 바로 중단
+    ///
+    /// 이전 실행의 중단 사유는 `finish`에서 지웁니다.
+    pub(crate) fn begin(&self, isolate: v8::IsolateHandle) {
+        let mut registered = self.state.isolate.lock().unwrap();
+        if self.state.reason.lock().unwrap().is_some() {
+            isolate.terminate_execution();
+        }
+        *registered = Some(isolate);
+    }
+
+    /// 실행 종료: isolate 핸들을 해제하고 중단 사유 반환
+    pub(crate) fn finish(&self) -> Option<InterruptReason> {
+        self.state.isolate.lock().unwrap().take();
+        self.state.reason.lock().unwrap().take()
+    }
+
+    /// 실행 중단 (먼저 기록된 사유가 우선)
+    pub(crate) fn interrupt(&self, reason: InterruptReason) {
+        self.state.reason.lock().unwrap().get_or_insert(reason);
+        if let Some(isolate) = self.state.isolate.lock().unwrap().as_ref() {
+            isolate.terminate_execution();
+        }
+        self.state.notify.notify_waiters();
+    }
+
+    /// 중단될 때까지 대기
+    async fn interrupted(&self) -> InterruptReason {
+        loop {
+            // notify_waiters는 생성된 Notified만 깨우므로 사유 확인 전에 먼저 생성
+            let notified = self.state.notify.notified();
+            if let Some(reason) = *self.state.reason.lock().unwrap() {
+                return reason;
+            }
+            notified.await;
+        }
+    }
+
+    /// 비동기 작업(모듈 로딩, 이벤트 루프)을 중단 신호와 경쟁시킴
+    ///
+    /// 타이머나 Promise를 기다리는 동안에는 `terminate_execution`이 효과가 없으므로
+    /// 대기 자체를 끊어야 합니다.
+    pub(crate) async fn race<T>(&self, fut: impl Future<Output = Result<T>>) -> Result<T> {
+        tokio::select! {
+            result = fut => result,
+            reason = self.interrupted() => Err(reason.into_error().into()),
+        }
+    }
+
+    /// 실행 결과에 중단 사유 반영 (중단된 경우 원래 에러 대신 중단 에러 반환)
+    pub(crate) fn resolve<T>(&self, result: Result<T>) -> Result<T> {
+        match self.finish() {
+            Some(reason) => Err(reason.into_error().into()),
+            None => result,
+        }
+    }
+}
+
+/// 제한 시간이 지나면 실행을 중단시키는 감시 스레드
+///
+/// drop되면 감시 스레드도 함께 종료됩니다.
+pub(crate) struct Watchdog {
+    _stop: Option<mpsc::Sender<()>>,
+}
+
+impl Watchdog {
+    pub(crate) fn start(cancel_handle: &CancelHandle, timeout: Option<Duration>) -> Self {
+        let Some(timeout) = timeout else {
+            return Self { _stop: None };
+        };
+
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let cancel_handle = cancel_handle.clone();
+        std::thread::spawn(move || {
+            if let Err(mpsc::RecvTimeoutError::Timeout) = stop_rx.recv_timeout(timeout) {
+                eprintln!("[Watchdog] 실행 시간 초과: {:?}", timeout);
+                cancel_handle.interrupt(InterruptReason::TimedOut(timeout));
+            }
+        });
+
+        Self {
+            _stop: Some(stop_tx),
+        }
+    }
+}
@@ -1,114 +1,179 @@
 use anyhow::Error as AnyhowError;
 use anyhow::Result;
+use deno_ast::{MediaType, ParseDiagnostic};
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
+use deno_core::error::JsError;
 use deno_core::{
-    extension, op2, FastString, FsModuleLoader, JsRuntime, ModuleLoadResponse, ModuleLoader,
-    ModuleSource, ModuleSourceCode, ModuleSpecifier, ModuleType, RequestedModuleType,
-    ResolutionKind, RuntimeOptions,
+    extension, op2, v8, FastString, FsModuleLoader, JsRuntime, ModuleLoadResponse, ModuleLoader,
+    ModuleSource, ModuleSourceCode, ModuleSpecifier, ModuleType, OpState, PollEventLoopOptions,
+    RequestedModuleType, ResolutionKind, RuntimeOptions,
 };
 use futures::FutureExt;
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::Mutex;
-
+use std::time::Duration;
+
+mod cjs;
+mod console;
+mod error;
+mod inspect;
+mod integrity;
+mod interrupt;
+mod mode;
+mod node_resolver;
 mod npm_resolver;
-pub use npm_resolver::NpmResolver;
-
-/// JavaScript 실행 결과를 저장하는 구조체
+mod npmrc;
+mod output;
+mod packument;
+mod semver;
+mod session;
+#[cfg(test)]
+mod test_util;
+mod transpile;
+mod value;
+use cjs::{
+    esm_facade, install_required_packages, is_commonjs, op_require_read, op_require_resolve,
+    RequireResolver,
+};
+use console::{
+    op_console_clear, op_console_count, op_console_dir, op_console_group, op_console_group_end,
+    op_console_log, op_console_table, op_console_time, op_console_time_log, op_console_trace,
+    ConsoleState,
+};
+pub use error::{ExecutionError, PartialOutput, ScriptError};
+pub use inspect::{format_args, inspect, InspectOptions, SpanStyle, StyledSpan, StyledText};
+pub use interrupt::CancelHandle;
+use interrupt::{InterruptReason, Watchdog};
+pub use mode::ExecutionMode;
+use mode::RunAs;
+use node_resolver::ImportTarget;
+pub use node_resolver::DEFAULT_CONDITIONS;
+use npm_resolver::parse_package_specifier;
+pub use npm_resolver::{DependencyGraph, InstalledPackage, NpmResolver};
+pub use npmrc::{NpmrcConfig, DEFAULT_REGISTRY};
+use output::OutputCollector;
+pub use output::{
+    ConsoleEntry, ConsoleEntryKind, ConsoleTable, ConsoleTableRow, ExecutionOutput, LogLevel,
+    OutputSink, SourceLocation, StackFrame,
+};
+pub use packument::DEFAULT_METADATA_TTL;
+use session::run_await_script;
+pub use session::{ReplSession, SessionId};
+use transpile::{needs_transpile, transpile, SourceMaps};
+pub use transpile::{JsxRuntime, Language};
+pub use value::{JsValuePreview, MapEntryPreview, PromiseState, PropertyPreview};
+
+/// 스크립트 실행 결과
 #[derive(Debug, Clone)]
-pub struct ExecutionOutput {
-    pub stdout: VecDeque<String>,
-    pub stderr: VecDeque<String>,
+pub struct ExecutionResult {
+    /// 콘솔 출력 (호출 순서대로)
+    pub output: ExecutionOutput,
+    /// 마지막 표현식의 값 (Promise면 이행된 값, ES 모듈로 실행된 경우 None)
+    pub value: Option<JsValuePreview>,
 }
 
-impl ExecutionOutput {
-    pub fn new() -> Self {
-        Self {
-            stdout: VecDeque::new(),
-            stderr: VecDeque::new(),
-        }
-    }
-
-    pub fn add_stdout(&mut self, message: String) {
-        self.stdout.push_back(message);
-    }
-
-    pub fn add_stderr(&mut self, message: String) {
-        self.stderr.push_back(message);
-    }
-
-    pub fn get_output(&self) -> String {
-        let mut output = Vec::new();
-
-        for line in &self.stdout {
-            output.push(line.clone());
-        }
-
-        for line in &self.stderr {
-            output.push(format!("[ERROR] {}", line));
-        }
+/// bootstrap.js 스크립트 이름 (콘솔 호출 위치를 찾을 때 건너뜀)
+const BOOTSTRAP_SCRIPT_NAME: &str = "[executejs:bootstrap.js]";
+
+/// 일반 스크립트로 실행하는 사용자 코드의 스크립트 이름
+const USER_CODE_SCRIPT_NAME: &str = "[executejs:user_code]";
+
+/// ES 모듈로 실행하는 사용자 코드의 URL
+const USER_CODE_MODULE_URL: &str = "file:///executejs/user_code.mjs";
+
+/// JS 예외나 변환 중 문법 오류면 위치 정보가 담긴 `ExecutionError::Exception`으로 변환
+/// (그 외 에러는 그대로)
+pub(crate) fn into_script_error(
+    error: AnyError,
+    code: &str,
+    is_user_file: &dyn Fn(&str) -> bool,
+) -> AnyError {
+    let error = match error.downcast::<JsError>() {
+        Ok(js_error) => ScriptError::from_js_error(&js_error, code, is_user_file),
+        Err(error) => match error.downcast::<ParseDiagnostic>() {
+            Ok(diagnostic) => ScriptError::from_parse_diagnostic(&diagnostic, code, is_user_file),
+            Err(error) => return error,
+        },
+    };
+    ExecutionError::Exception(error).into()
+}
 
-        output.join("\n")
-    }
+/// 현재 JsRuntime의 출력 수집기에 콘솔 출력 추가
+fn push_entry(state: &mut OpState, entry: ConsoleEntry) {
+    state.borrow_mut::<OutputCollector>().push(entry);
 }
 
-/// 전역 출력 버퍼 (스레드 안전)
-static OUTPUT_BUFFER: Mutex<Option<Arc<Mutex<ExecutionOutput>>>> = Mutex::new(None);
+/// 실행 시작 시 이전 출력을 비우고 이번 실행의 실시간 출력 sink 지정
+pub(crate) fn begin_output(js_runtime: &mut JsRuntime, sink: Option<OutputSink>) {
+    js_runtime
+        .op_state()
+        .borrow_mut()
+        .borrow_mut::<OutputCollector>()
+        .begin(sink);
+}
 
-/// console.log를 위한 op 함수
-#[op2(fast)]
-#[string]
-fn op_console_log(#[string] message: String) -> Result<(), AnyError> {
-    if let Ok(buffer_guard) = OUTPUT_BUFFER.lock() {
-        if let Some(buffer) = buffer_guard.as_ref() {
-            if let Ok(mut output) = buffer.lock() {
-                output.add_stdout(message);
-            }
-        }
-    }
-    Ok(())
+/// 실행이 끝난 뒤 모은 출력을 꺼냄
+pub(crate) fn finish_output(js_runtime: &mut JsRuntime) -> ExecutionOutput {
+    js_runtime
+        .op_state()
+        .borrow_mut()
+        .borrow_mut::<OutputCollector>()
+        .finish()
 }
 
 /// alert를 위한 op 함수
 #[op2(fast)]
 #[string]
-fn op_alert(#[string] message: String) -> Result<(), AnyError> {
-    if let Ok(buffer_guard) = OUTPUT_BUFFER.lock() {
-        if let Some(buffer) = buffer_guard.as_ref() {
-            if let Ok(mut output) = buffer.lock() {
-                output.add_stdout(format!("[ALERT] {}", message));
-            }
-        }
-    }
+fn op_alert(state: &mut OpState, #[string] message: String) -> Result<(), AnyError> {
+    push_entry(state, ConsoleEntry::text(LogLevel::Alert, message));
     Ok(())
 }
 
 /// print를 위한 op 함수 (Deno.core.print 대체)
 #[op2(fast)]
 #[string]
-fn op_custom_print(#[string] message: String, is_err: bool) -> Result<(), AnyError> {
-    if let Ok(buffer_guard) = OUTPUT_BUFFER.lock() {
-        if let Some(buffer) = buffer_guard.as_ref() {
-            if let Ok(mut output) = buffer.lock() {
-                if is_err {
-                    output.add_stderr(message);
-                } else {
-                    output.add_stdout(message);
-                }
-            }
-        }
-    }
+fn op_custom_print(
+    state: &mut OpState,
+    #[string] message: String,
+    is_err: bool,
+) -> Result<(), AnyError> {
+    let level = if is_err {
+        LogLevel::Error
+    } else {
+        LogLevel::Log
+    };
+    push_entry(state, ConsoleEntry::text(level, message));
     Ok(())
 }
 
-/// 커스텀 확장 정의
+// 커스텀 확장 정의
 extension!(
     executejs_runtime,
-    ops = [op_console_log, op_alert, op_custom_print],
+    ops = [
+        op_console_log,
+        op_console_group,
+        op_console_group_end,
+        op_console_count,
+        op_console_time,
+        op_console_time_log,
+        op_console_table,
+        op_console_trace,
+        op_console_dir,
+        op_console_clear,
+        op_alert,
+        op_custom_print,
+        op_require_resolve,
+        op_require_read,
+    ],
+    state = |state| {
+        state.put(ConsoleState::default());
+        state.put(OutputCollector::default());
+    },
 );
 
 /// npm 패키지를 지원하는 모듈 로더
@@ -117,16 +182,154 @@ pub struct NpmModuleLoader {
     npm_resolver: Arc<Mutex<NpmResolver>>,
     /// npm: URL과 실제 파일 경로 매핑
     npm_path_map: Arc<Mutex<HashMap<String, PathBuf>>>,
+    /// `.jsx`/`.tsx` 모듈 변환 방식
+    jsx: JsxRuntime,
+    /// 변환한 모듈의 소스 맵 (`get_source_map`에서 사용)
+    source_maps: SourceMaps,
+    /// 설치한 npm 패키지 버전 (패키지 안의 bare specifier 해석과 중복 제거에 사용)
+    dependency_graph: DependencyGraph,
 }
 
 impl NpmModuleLoader {
     pub fn new() -> Result<Self> {
+        Self::with_jsx_runtime(JsxRuntime::default())
+    }
+
+    /// JSX 변환 방식을 지정하여 생성
+    pub fn with_jsx_runtime(jsx: JsxRuntime) -> Result<Self> {
         Ok(Self {
             fs_loader: FsModuleLoader,
             npm_resolver: Arc::new(Mutex::new(NpmResolver::new()?)),
             npm_path_map: Arc::new(Mutex::new(HashMap::new())),
+            jsx,
+            source_maps: SourceMaps::default(),
+            dependency_graph: DependencyGraph::default(),
+        })
+    }
+
+    /// package.json `exports`/`imports`에서 고를 조건 지정
+    pub fn with_conditions(self, conditions: Vec<String>) -> Self {
+        let resolver = self
+            .npm_resolver
+            .lock()
+            .unwrap()
+            .clone()
+            .with_conditions(conditions);
+        Self {
+            npm_resolver: Arc::new(Mutex::new(resolver)),
+            ..self
+        }
+    }
+
+    /// 오프라인 모드 지정 (캐시에 설치된 npm 패키지만 사용)
+    pub fn with_offline(self, offline: bool) -> Self {
+        let resolver = self
+            .npm_resolver
+            .lock()
+            .unwrap()
+            .clone()
+            .with_offline(offline);
+        Self {
+            npm_resolver: Arc::new(Mutex::new(resolver)),
+            ..self
+        }
+    }
+
+    /// 이 로더가 변환한 모듈의 소스 맵 (사용자 코드의 소스 맵도 여기에 등록)
+    pub(crate) fn source_maps(&self) -> SourceMaps {
+        self.source_maps.clone()
+    }
+
+    /// ES 모듈과 같은 캐시, 조건, 의존성 그래프를 쓰는 `require` 해석기
+    pub(crate) fn require_resolver(&self, cancel_handle: &CancelHandle) -> RequireResolver {
+        let npm_resolver = self.npm_resolver.lock().unwrap().clone();
+        RequireResolver::new(
+            npm_resolver,
+            self.dependency_graph.clone(),
+            cancel_handle.clone(),
+        )
+    }
+
+    /// npm 캐시에 설치된 패키지 안의 파일인지
+    fn is_package_file(&self, specifier: &ModuleSpecifier) -> bool {
+        specifier.to_file_path().is_ok_and(|path| {
+            self.npm_resolver
+                .lock()
+                .unwrap()
+                .package_of(&path)
+                .is_some()
         })
     }
+
+    /// npm 캐시 안의 파일에서 가져온 모듈을 Node 해석 규칙으로 해석
+    ///
+    /// - bare specifier: 패키지 의존성으로 보고 설치된 버전의 `npm:` specifier로 변환
+    /// - `#` specifier: 패키지 package.json의 `imports` 필드로 해석
+    /// - 상대/절대 경로: 확장자를 붙이거나 디렉토리의 `index.js`를 찾음
+    ///
+    /// 캐시 밖의 파일이 가져온 모듈이면 `None`을 반환합니다.
+    fn resolve_in_package(
+        &self,
+        specifier: &str,
+        referrer: &str,
+    ) -> Result<Option<ModuleSpecifier>, AnyhowError> {
+        let Some(referrer_path) = ModuleSpecifier::parse(referrer)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+        else {
+            return Ok(None);
+        };
+        let resolver = self.npm_resolver.lock().unwrap();
+        if resolver.package_of(&referrer_path).is_none() {
+            return Ok(None);
+        }
+
+        if specifier.starts_with('#') {
+            let target =
+                node_resolver::resolve_imports(specifier, &referrer_path, resolver.conditions())?;
+            return match target {
+                ImportTarget::File(path) => file_specifier(&path).map(Some),
+                ImportTarget::Package(target) => {
+                    drop(resolver);
+                    self.resolve_in_package(&target, referrer)
+                }
+            };
+        }
+
+        let is_relative = specifier.starts_with("./")
+            || specifier.starts_with("../")
+            || specifier.starts_with('/');
+        if is_relative {
+            let url = deno_core::resolve_import(specifier, referrer)?;
+            let resolved = url
+                .to_file_path()
+                .ok()
+                .and_then(|path| node_resolver::resolve_file(&path));
+            return match resolved {
+                Some(path) => file_specifier(&path).map(Some),
+                None => Ok(Some(url)),
+            };
+        }
+
+        // URL(`node:`, `https:` 등)은 그대로 둠
+        if ModuleSpecifier::parse(specifier).is_ok() {
+            return Ok(None);
+        }
+        let npm_specifier = resolver
+            .resolve_package_import(specifier, &referrer_path, &self.dependency_graph)
+            .ok_or_else(|| {
+                type_error(format!(
+                    "패키지를 찾을 수 없습니다: {} (referrer: {})",
+                    specifier, referrer
+                ))
+            })?;
+        Ok(Some(ModuleSpecifier::parse(&npm_specifier)?))
+    }
+}
+
+fn file_specifier(path: &Path) -> Result<ModuleSpecifier, AnyhowError> {
+    ModuleSpecifier::from_file_path(path)
+        .map_err(|_| type_error(format!("파일 경로를 URL로 변환할 수 없습니다: {:?}", path)))
 }
 
 impl ModuleLoader for NpmModuleLoader {
@@ -151,7 +354,7 @@ impl ModuleLoader for NpmModuleLoader {
             ModuleSpecifier::parse(specifier).map_err(|e| {
                 eprintln!("[NpmModuleLoader::resolve] 모듈 스펙 해석 실패: {}", e);
                 let msg = format!("모듈 스펙 해석 실패: {}", e);
-                type_error(msg).into()
+                type_error(msg)
             })
         } else {
             // npm 패키지 내부의 상대 경로 처리
@@ -199,6 +402,11 @@ impl ModuleLoader for NpmModuleLoader {
                 referrer.to_string()
             };
 
+            // npm 패키지 안의 모듈은 Node 해석 규칙 적용 (bare specifier, imports, 확장자 생략)
+            if let Some(resolved) = self.resolve_in_package(specifier, &actual_referrer)? {
+                return Ok(resolved);
+            }
+
             eprintln!(
                 "[NpmModuleLoader::resolve] 일반 파일 시스템 모듈로 처리 (referrer: {})",
                 actual_referrer
@@ -226,41 +434,21 @@ impl ModuleLoader for NpmModuleLoader {
         );
 
         // npm: 프로토콜 처리
-        if specifier_str.starts_with("npm:") {
+        if let Some(package_spec) = specifier_str.strip_prefix("npm:") {
             eprintln!(
                 "[NpmModuleLoader::load] npm: 프로토콜 감지, 패키지 다운로드 시작: {}",
                 specifier_str
             );
-            let package_spec = &specifier_str[4..];
-
-            // 패키지명과 버전 파싱
-            let (package_name, version) = if let Some(at_pos) = package_spec.rfind('@') {
-                // 스코프 패키지 처리 (@scope/package@version)
-                if package_spec.starts_with('@') {
-                    // @scope/package@version 형식
-                    let after_first_at = &package_spec[1..];
-                    if let Some(second_at_pos) = after_first_at.rfind('@') {
-                        // second_at_pos는 after_first_at 기준이므로 package_spec 기준으로는 +1 필요
-                        let scope_and_name = &package_spec[..=(second_at_pos + 1)];
-                        let version = &package_spec[second_at_pos + 2..];
-                        (scope_and_name.to_string(), Some(version.to_string()))
-                    } else {
-                        // @scope/package (버전 없음)
-                        (package_spec.to_string(), None)
-                    }
-                } else {
-                    // package@version
-                    let (name, version) = package_spec.split_at(at_pos);
-                    (name.to_string(), Some(version[1..].to_string()))
-                }
-            } else {
-                (package_spec.to_string(), None)
-            };
+
+            // 패키지명, 버전, 하위 경로 파싱 (예: npm:react@18/jsx-runtime)
+            let (package_name, version, subpath) = parse_package_specifier(package_spec);
 
             // 비동기 로드
-            // 필요한 데이터를 먼저 복사하고 락 해제
-            let cache_dir = npm_resolver.lock().unwrap().cache_dir().to_path_buf();
-            let registry_url = npm_resolver.lock().unwrap().registry_url().to_string();
+            // 리졸버 설정을 복사하고 락 해제
+            let resolver = npm_resolver.lock().unwrap().clone();
+            let jsx = self.jsx.clone();
+            let source_maps = self.source_maps.clone();
+            let dependency_graph = self.dependency_graph.clone();
 
             let fut = async move {
                 eprintln!(
@@ -268,43 +456,43 @@ impl ModuleLoader for NpmModuleLoader {
                     package_name, version
                 );
 
-                // 패키지 다운로드 및 설치 (독립적인 리졸버 생성)
-                eprintln!("[NpmModuleLoader::load] NpmResolver 생성 중...");
-                let resolver =
-                    NpmResolver::with_cache_dir(cache_dir, registry_url).map_err(|e| {
-                        eprintln!("[NpmModuleLoader::load] 리졸버 생성 실패: {}", e);
-                        let msg = format!("리졸버 생성 실패: {}", e);
-                        type_error(msg)
-                    })?;
-                eprintln!("[NpmModuleLoader::load] 리졸버 생성 완료");
-
+                // 패키지 다운로드 및 설치
                 eprintln!(
-                    "[NpmModuleLoader::load] install_package 호출: {}@{:?}",
+                    "[NpmModuleLoader::load] install_with_dependencies 호출: {}@{:?}",
                     package_name,
                     version.as_deref()
                 );
-                let package_dir = resolver
-                    .install_package(&package_name, version.as_deref())
+                let installed = resolver
+                    .install_with_dependencies(&package_name, version.as_deref(), &dependency_graph)
                     .await
                     .map_err(|e| {
                         eprintln!("[NpmModuleLoader::load] npm 패키지 다운로드 실패: {}", e);
                         let msg = format!("npm 패키지 다운로드 실패: {}", e);
                         type_error(msg)
                     })?;
+                let package_dir = installed.dir;
                 eprintln!(
                     "[NpmModuleLoader::load] 패키지 다운로드 완료: {:?}",
                     package_dir
                 );
 
-                // 진입점 찾기
+                // 진입점 찾기 (하위 경로가 있으면 해당 파일)
                 eprintln!("[NpmModuleLoader::load] 진입점 찾기 시작...");
-                let entry_point = resolver.find_entry_point(&package_dir).map_err(|e| {
+                let entry_point = match &subpath {
+                    Some(subpath) => resolver.find_subpath_entry(&package_dir, subpath),
+                    None => resolver.find_entry_point(&package_dir),
+                }
+                .map_err(|e| {
                     eprintln!("[NpmModuleLoader::load] 진입점 찾기 실패: {}", e);
                     let msg = format!("진입점 찾기 실패: {}", e);
                     type_error(msg)
                 })?;
                 eprintln!("[NpmModuleLoader::load] 진입점: {:?}", entry_point);
 
+                // 범위나 태그로 요청하거나 패키지 안에서 상대 경로로 가져와도 한 모듈이 되도록
+                // 진입점 파일의 file:// URL로 등록
+                let found = file_specifier(&entry_point)?;
+
                 // 타입 정의 파일 찾기
                 eprintln!("[NpmModuleLoader::load] 타입 정의 파일 찾기 시작...");
                 let type_def = resolver.find_type_definitions(&package_dir).unwrap_or(None);
@@ -318,13 +506,12 @@ impl ModuleLoader for NpmModuleLoader {
                 }
 
                 // npm: URL과 실제 파일 경로 매핑 저장
-                let specifier_str_for_map = specifier.as_str().to_string();
                 {
                     let mut path_map = npm_path_map.lock().unwrap();
-                    path_map.insert(specifier_str_for_map.clone(), entry_point.clone());
+                    path_map.insert(specifier.as_str().to_string(), entry_point.clone());
                     eprintln!(
                         "[NpmModuleLoader::load] 경로 매핑 저장: {} -> {:?}",
-                        specifier_str_for_map, entry_point
+                        specifier, entry_point
                     );
                 }
 
@@ -340,48 +527,39 @@ impl ModuleLoader for NpmModuleLoader {
                     code.len()
                 );
 
-                // 파일 확장자에 따라 ModuleType 결정
-                // Deno Core는 TypeScript를 자동으로 처리하므로 JavaScript로 설정
-                // 실제 TypeScript 파일은 런타임에서 자동 변환됨
-                let file_ext = entry_point
-                    .extension()
-                    .and_then(|e| e.to_str())
-                    .unwrap_or("");
-
-                let module_type = match file_ext {
-                    "ts" | "tsx" | "mts" | "cts" => {
-                        eprintln!(
-                            "[NpmModuleLoader::load] TypeScript 모듈로 감지 (확장자: {})",
-                            file_ext
-                        );
-                        // Deno Core는 TypeScript도 JavaScript로 처리 가능
-                        ModuleType::JavaScript
-                    }
-                    "jsx" => {
-                        eprintln!("[NpmModuleLoader::load] JSX 모듈로 감지");
-                        ModuleType::JavaScript
-                    }
-                    _ => {
-                        eprintln!("[NpmModuleLoader::load] JavaScript 모듈로 감지");
-                        ModuleType::JavaScript
-                    }
-                };
-
-                // ModuleSource 생성
+                // ModuleSource 생성 (TypeScript/JSX는 확장자를 보고 변환)
                 eprintln!("[NpmModuleLoader::load] ModuleSource 생성 중...");
-                let module_code = ModuleSourceCode::String(FastString::from(code));
-                let module_source = ModuleSource::new(
-                    module_type,
-                    module_code,
-                    &specifier,
-                    None, // code_cache
-                );
+                let module_source =
+                    module_source(&specifier, &found, &entry_point, code, &jsx, &source_maps)?;
                 eprintln!("[NpmModuleLoader::load] ModuleSource 생성 완료");
 
                 Ok(module_source)
             };
 
             ModuleLoadResponse::Async(fut.boxed())
+        } else if module_specifier.scheme() == "file"
+            && match MediaType::from_specifier(module_specifier) {
+                MediaType::Cjs => true,
+                MediaType::JavaScript => self.is_package_file(module_specifier),
+                media_type => needs_transpile(media_type),
+            }
+        {
+            // TypeScript/JSX와 CommonJS 파일은 FsModuleLoader가 처리하지 못하므로 직접 읽어서 변환
+            let result = module_specifier
+                .to_file_path()
+                .map_err(|_| type_error(format!("파일 경로가 아닙니다: {}", specifier_str)))
+                .and_then(|path| {
+                    let code = fs::read_to_string(&path)?;
+                    module_source(
+                        module_specifier,
+                        module_specifier,
+                        &path,
+                        code,
+                        &self.jsx,
+                        &self.source_maps,
+                    )
+                });
+            ModuleLoadResponse::Sync(result)
         } else {
             // 일반 파일 시스템 모듈
             self.fs_loader.load(
@@ -392,128 +570,425 @@ impl ModuleLoader for NpmModuleLoader {
             )
         }
     }
+
+    fn get_source_map(&self, file_name: &str) -> Option<Vec<u8>> {
+        // 변환하지 않은 모듈은 소스 맵이 없으므로 V8 위치를 그대로 사용
+        self.source_maps.get(file_name)
+    }
+}
+
+/// 읽어 온 모듈 코드로 ModuleSource 생성
+///
+/// `.ts`/`.mts`/`.cts` 등은 타입을 제거하고, `.jsx`/`.tsx`는 `jsx` 설정대로 JSX를 변환해서
+/// JavaScript 모듈로 만듭니다. 변환한 모듈의 소스 맵은 `source_maps`에 등록합니다.
+/// CommonJS 파일은 `require`로 실행해서 `module.exports`를 내보내는 모듈로 바꿉니다.
+/// `found`가 요청한 `specifier`와 다르면 리다이렉트로 등록해서 같은 모듈을 한 번만 평가합니다.
+fn module_source(
+    specifier: &ModuleSpecifier,
+    found: &ModuleSpecifier,
+    path: &Path,
+    code: String,
+    jsx: &JsxRuntime,
+    source_maps: &SourceMaps,
+) -> Result<ModuleSource, AnyError> {
+    let media_type = MediaType::from_path(path);
+    let code = if is_commonjs(path, &code) {
+        esm_facade(path, &code)
+    } else if needs_transpile(media_type) {
+        let transpiled = transpile(found, media_type, code, true, jsx)?;
+        source_maps.insert(found.as_str(), transpiled.source_map);
+        transpiled.code
+    } else {
+        code
+    };
+    let code = ModuleSourceCode::String(FastString::from(code));
+    if found == specifier {
+        Ok(ModuleSource::new(
+            ModuleType::JavaScript,
+            code,
+            specifier,
+            None, // code_cache
+        ))
+    } else {
+        Ok(ModuleSource::new_with_redirect(
+            ModuleType::JavaScript,
+            code,
+            specifier,
+            found,
+            None, // code_cache
+        ))
+    }
+}
+
+/// 커스텀 확장, npm 모듈 로더, bootstrap.js가 적용된 JsRuntime 생성
+///
+/// `inspector`가 true면 REPL 평가(`Runtime.evaluate`)에 필요한 인스펙터를 함께 생성합니다.
+/// 힙 한도가 지정되면 한도에 가까워졌을 때 `cancel_handle`로 스크립트를 중단합니다.
+pub(crate) fn create_runtime(
+    options: &ExecutorOptions,
+    cancel_handle: &CancelHandle,
+    inspector: bool,
+) -> Result<JsRuntime> {
+    // 커스텀 모듈 로더 생성 (npm 지원)
+    let module_loader = NpmModuleLoader::with_jsx_runtime(options.jsx.clone()).map(|loader| {
+        let loader = loader.with_offline(options.npm_offline);
+        match &options.npm_conditions {
+            Some(conditions) => loader.with_conditions(conditions.clone()),
+            None => loader,
+        }
+    });
+    let (module_loader, source_maps, require_resolver) = match module_loader {
+        Ok(loader) => {
+            let source_maps = loader.source_maps();
+            let require_resolver = loader.require_resolver(cancel_handle);
+            (
+                Rc::new(loader) as Rc<dyn ModuleLoader>,
+                source_maps,
+                Some(require_resolver),
+            )
+        }
+        Err(e) => {
+            // npm 리졸버 생성 실패 시 기본 로더 사용
+            eprintln!("npm 모듈 로더 초기화 실패 (기본 로더 사용): {}", e);
+            (
+                Rc::new(FsModuleLoader) as Rc<dyn ModuleLoader>,
+                SourceMaps::default(),
+                None,
+            )
+        }
+    };
+
+    // 힙 한도 설정 (기본값은 V8 기본 한도)
+    let create_params = options
+        .max_heap_size
+        .map(|max_heap_size| v8::CreateParams::default().heap_limits(0, max_heap_size));
+
+    // JsRuntime 생성
+    let mut js_runtime = JsRuntime::new(RuntimeOptions {
+        module_loader: Some(module_loader),
+        extensions: vec![executejs_runtime::init_ops()],
+        create_params,
+        inspector,
+        ..Default::default()
+    });
+
+    // 사용자 코드를 변환할 때 소스 맵을 등록할 수 있도록 OpState에 보관
+    js_runtime.op_state().borrow_mut().put(source_maps);
+    // require가 ES 모듈 로더와 같은 캐시와 의존성 그래프를 쓰도록 보관
+    if let Some(require_resolver) = require_resolver {
+        js_runtime.op_state().borrow_mut().put(require_resolver);
+    }
+
+    // 힙 한도에 가까워지면 프로세스가 abort되기 전에 스크립트 중단
+    if let Some(max_heap_size) = options.max_heap_size {
+        let cancel_handle = cancel_handle.clone();
+        js_runtime.add_near_heap_limit_callback(move |current_limit, _initial_limit| {
+            eprintln!(
+                "[DenoExecutor] 힙 한도 근접: {} bytes, 스크립트 중단",
+                current_limit
+            );
+            cancel_handle.interrupt(InterruptReason::OutOfMemory(max_heap_size));
+            // 종료 처리가 끝날 때까지 여유 공간을 주기 위해 한도를 늘림
+            current_limit * 2
+        });
+    }
+
+    // bootstrap.js 실행하여 커스텀 API 설정
+    let bootstrap_code = include_str!("bootstrap.js");
+    if let Err(e) = js_runtime.execute_script(BOOTSTRAP_SCRIPT_NAME, bootstrap_code) {
+        return Err(anyhow::anyhow!("Bootstrap 실행 실패: {}", e));
+    }
+
+    Ok(js_runtime)
+}
+
+/// 런타임의 모듈 로더와 공유하는 소스 맵 저장소
+pub(crate) fn source_maps(js_runtime: &mut JsRuntime) -> SourceMaps {
+    js_runtime
+        .op_state()
+        .borrow()
+        .borrow::<SourceMaps>()
+        .clone()
+}
+
+/// 완료 값이 Promise면 이벤트 루프를 돌리며 이행된 값을 기다림
+pub(crate) async fn resolve_completion_value(
+    js_runtime: &mut JsRuntime,
+    value: v8::Global<v8::Value>,
+) -> Result<v8::Global<v8::Value>> {
+    let is_promise = {
+        let scope = &mut js_runtime.handle_scope();
+        v8::Local::new(scope, &value).is_promise()
+    };
+    if !is_promise {
+        return Ok(value);
+    }
+
+    let resolved = js_runtime.resolve(value);
+    js_runtime
+        .with_event_loop_promise(resolved, PollEventLoopOptions::default())
+        .await
+}
+
+/// V8 값의 구조화된 미리보기 생성
+pub(crate) fn preview_global(
+    js_runtime: &mut JsRuntime,
+    value: v8::Global<v8::Value>,
+) -> JsValuePreview {
+    let scope = &mut js_runtime.handle_scope();
+    let local = v8::Local::new(scope, value);
+    JsValuePreview::from_v8(scope, local)
+}
+
+/// 사용자 코드를 스크립트 또는 ES 모듈로 실행하고 이벤트 루프가 빌 때까지 대기
+///
+/// 스크립트로 실행한 경우 마지막 표현식의 값을 미리보기로 반환합니다.
+fn run_user_code(
+    js_runtime: &mut JsRuntime,
+    code: String,
+    language: Language,
+    is_module: bool,
+    jsx: &JsxRuntime,
+    cancel_handle: &CancelHandle,
+) -> Result<Option<JsValuePreview>> {
+    // 코드 실행
+    eprintln!(
+        "[DenoExecutor] 코드 실행 시작, 코드 길이: {} bytes",
+        code.len()
+    );
+    eprintln!(
+        "[DenoExecutor] 코드 내용 (처음 200자): {}",
+        &code.chars().take(200).collect::<String>()
+    );
+
+    // 이벤트 루프 실행을 위한 런타임 핸들
+    let rt = tokio::runtime::Handle::current();
+
+    // JS 예외는 사용자 코드 위치를 담은 ScriptError로 변환
+    let is_user_file = |file: &str| file == USER_CODE_SCRIPT_NAME || file == USER_CODE_MODULE_URL;
+    let specifier =
+        ModuleSpecifier::parse(USER_CODE_MODULE_URL).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    // 오류 위치는 소스 맵으로 원래 코드 기준이 되므로 소스 줄도 원래 코드에서 읽음
+    let source = code.clone();
+    let script_error = |error| into_script_error(error, &source, &is_user_file);
+
+    // TypeScript/JSX는 변환한 코드로 실행하고 소스 맵 등록
+    let media_type = language.media_type();
+    let code = if needs_transpile(media_type) {
+        eprintln!("[DenoExecutor] {:?} 코드를 JavaScript로 변환...", language);
+        let transpiled =
+            transpile(&specifier, media_type, code, is_module, jsx).map_err(script_error)?;
+        let file_name = if is_module {
+            USER_CODE_MODULE_URL
+        } else {
+            USER_CODE_SCRIPT_NAME
+        };
+        source_maps(js_runtime).insert(file_name, transpiled.source_map);
+        transpiled.code
+    } else {
+        code
+    };
+
+    // require op 안에서는 패키지를 설치할 수 없으므로 require할 npm 패키지를 미리 설치
+    rt.block_on(cancel_handle.race(install_required_packages(js_runtime, &code)))
+        .map_err(script_error)?;
+
+    let completion_value = if is_module {
+        // ES 모듈로 실행
+        eprintln!("[DenoExecutor] ES 모듈로 실행 시도...");
+        eprintln!(
+            "[DenoExecutor] load_main_es_module_from_code 호출: {}",
+            specifier
+        );
+        let module_id = rt
+            .block_on(cancel_handle.race(async {
+                js_runtime
+                    .load_main_es_module_from_code(&specifier, code)
+                    .await
+            }))
+            .map_err(script_error)?;
+
+        eprintln!("[DenoExecutor] 모듈 로드 완료, ModuleId: {}", module_id);
+
+        // 모듈 평가 (비동기)
+        eprintln!("[DenoExecutor] mod_evaluate 호출...");
+        rt.block_on(cancel_handle.race(async { js_runtime.mod_evaluate(module_id).await }))
+            .map_err(script_error)?;
+
+        eprintln!("[DenoExecutor] mod_evaluate 완료");
+        None
+    } else {
+        // 일반 스크립트로 실행
+        eprintln!("[DenoExecutor] 일반 스크립트로 실행...");
+        let value = js_runtime
+            .execute_script(USER_CODE_SCRIPT_NAME, code)
+            .map_err(script_error)?;
+        eprintln!("[DenoExecutor] execute_script 완료");
+
+        // 마지막 표현식이 Promise면 이행된 값 사용
+        let value = rt
+            .block_on(cancel_handle.race(resolve_completion_value(js_runtime, value)))
+            .map_err(script_error)?;
+        Some(value)
+    };
+
+    // 이벤트 루프 실행 (Promise 처리 및 모듈 로딩 완료 대기)
+    eprintln!("[DenoExecutor] 이벤트 루프 실행 시작...");
+    rt.block_on(cancel_handle.race(async { js_runtime.run_event_loop(Default::default()).await }))
+        .map_err(script_error)?;
+    eprintln!("[DenoExecutor] 이벤트 루프 완료");
+
+    Ok(completion_value.map(|value| preview_global(js_runtime, value)))
+}
+
+/// DenoExecutor 실행 옵션
+#[derive(Debug, Clone, Default)]
+pub struct ExecutorOptions {
+    /// 실행 제한 시간 (None이면 제한 없음)
+    pub timeout: Option<Duration>,
+    /// V8 최대 힙 크기 (bytes, None이면 V8 기본값)
+    pub max_heap_size: Option<usize>,
+    /// 콘솔 출력을 실행 도중에 바로 받을 sink (결과의 `output`에도 그대로 남음)
+    pub output_sink: Option<OutputSink>,
+    /// JSX/TSX 코드와 `.jsx`/`.tsx` 모듈의 JSX 변환 방식
+    pub jsx: JsxRuntime,
+    /// 사용자 코드를 스크립트와 ES 모듈 중 어떻게 실행할지 (기본값은 코드를 보고 자동 결정)
+    pub mode: ExecutionMode,
+    /// npm 패키지의 `exports`/`imports`에서 고를 조건 (None이면 `DEFAULT_CONDITIONS`)
+    pub npm_conditions: Option<Vec<String>>,
+    /// npm 패키지를 레지스트리에 요청하지 않고 캐시에서만 찾기 (캐시에 없으면 오류)
+    pub npm_offline: bool,
 }
 
 /// JavaScript 실행기 (Deno Core 기반)
 pub struct DenoExecutor {
-    output_buffer: Arc<Mutex<ExecutionOutput>>,
+    options: ExecutorOptions,
+    cancel_handle: CancelHandle,
+    /// REPL 세션 (세션 ID별로 JsRuntime을 유지)
+    sessions: HashMap<SessionId, ReplSession>,
 }
 
 impl DenoExecutor {
     /// 새로운 DenoExecutor 인스턴스 생성
     pub async fn new() -> Result<Self> {
-        // 출력 버퍼 생성
-        let output_buffer = Arc::new(Mutex::new(ExecutionOutput::new()));
+        Self::with_options(ExecutorOptions::default()).await
+    }
 
-        // 전역 버퍼에 설정
-        {
-            let mut global_buffer = OUTPUT_BUFFER.lock().unwrap();
-            *global_buffer = Some(output_buffer.clone());
-        }
+    /// 실행 옵션을 지정하여 생성
+    pub async fn with_options(options: ExecutorOptions) -> Result<Self> {
+        Ok(Self {
+            options,
+            cancel_handle: CancelHandle::default(),
+            sessions: HashMap::new(),
+        })
+    }
+
+    /// 실행 중인 `execute_script`를 다른 스레드에서 취소하기 위한 핸들
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel_handle.clone()
+    }
+
+    /// 새 REPL 세션 생성
+    ///
+    /// 세션은 전용 스레드에서 하나의 JsRuntime을 유지하므로,
+    /// 이전 실행에서 선언한 변수를 다음 실행에서 그대로 사용할 수 있습니다.
+    pub async fn create_session(&mut self) -> Result<SessionId> {
+        let session = ReplSession::spawn(self.options.clone()).await?;
+        let id = session.id();
+        self.sessions.insert(id, session);
+        Ok(id)
+    }
+
+    /// 세션 핸들 반환 (락을 잡은 채로 실행을 기다리지 않도록 복제해서 사용)
+    pub fn session(&self, id: SessionId) -> Option<ReplSession> {
+        self.sessions.get(&id).cloned()
+    }
+
+    /// 세션의 전역 스코프에서 코드 실행
+    pub async fn execute_in_session(
+        &mut self,
+        id: SessionId,
+        code: &str,
+    ) -> Result<ExecutionResult> {
+        let session = self
+            .session(id)
+            .ok_or_else(|| anyhow::anyhow!("세션을 찾을 수 없습니다: {}", id))?;
+        session.execute(code).await
+    }
+
+    /// 세션의 JsRuntime을 새로 만들어 전역 상태 초기화
+    pub async fn reset_session(&mut self, id: SessionId) -> Result<()> {
+        let session = self
+            .session(id)
+            .ok_or_else(|| anyhow::anyhow!("세션을 찾을 수 없습니다: {}", id))?;
+        session.reset().await
+    }
 
-        Ok(Self { output_buffer })
+    /// 세션 종료 (세션 스레드와 JsRuntime 해제)
+    pub fn dispose_session(&mut self, id: SessionId) -> Result<()> {
+        self.sessions
+            .remove(&id)
+            .map(|_| ())
+            .ok_or_else(|| anyhow::anyhow!("세션을 찾을 수 없습니다: {}", id))
     }
 
     /// JavaScript 코드 실행
-    pub async fn execute_script(&mut self, _filename: &str, code: &str) -> Result<String> {
-        // 출력 버퍼 초기화
-        {
-            let mut output = self.output_buffer.lock().unwrap();
-            *output = ExecutionOutput::new();
-        }
+    ///
+    /// `filename`의 확장자가 `.ts`/`.mts`/`.cts`면 TypeScript로 실행합니다.
+    pub async fn execute_script(&mut self, filename: &str, code: &str) -> Result<ExecutionResult> {
+        self.execute_script_with_language(code, Language::from_filename(filename))
+            .await
+    }
 
+    /// 언어를 지정하여 코드 실행 (TypeScript는 타입을 제거한 뒤 실행)
+    pub async fn execute_script_with_language(
+        &mut self,
+        code: &str,
+        language: Language,
+    ) -> Result<ExecutionResult> {
         // 코드를 클로저로 캡처
         let code = code.to_string();
-        let output_buffer = self.output_buffer.clone();
+        let cancel_handle = self.cancel_handle.clone();
+        let options = self.options.clone();
 
         // 별도 스레드에서 Deno Core 실행 (Send 트레이트 문제 해결)
         let result = tokio::task::spawn_blocking(move || {
-            // 커스텀 모듈 로더 생성 (npm 지원)
-            let module_loader = match NpmModuleLoader::new() {
-                Ok(loader) => Rc::new(loader) as Rc<dyn ModuleLoader>,
-                Err(e) => {
-                    // npm 리졸버 생성 실패 시 기본 로더 사용
-                    eprintln!("npm 모듈 로더 초기화 실패 (기본 로더 사용): {}", e);
-                    Rc::new(FsModuleLoader) as Rc<dyn ModuleLoader>
-                }
-            };
-
-            // JsRuntime 생성
-            let mut js_runtime = JsRuntime::new(RuntimeOptions {
-                module_loader: Some(module_loader),
-                extensions: vec![executejs_runtime::init_ops()],
-                ..Default::default()
-            });
-
-            // bootstrap.js 실행하여 커스텀 API 설정
-            let bootstrap_code = include_str!("bootstrap.js");
-            if let Err(e) = js_runtime.execute_script("[executejs:bootstrap.js]", bootstrap_code) {
-                return Err(anyhow::anyhow!("Bootstrap 실행 실패: {}", e));
-            }
-
-            // 코드 실행
+            // 스크립트/ES 모듈 결정 (Auto면 코드를 파싱해서 모듈 구문 확인)
+            let run_as = options.mode.run_as(&code, language, &options.jsx);
             eprintln!(
-                "[DenoExecutor] 코드 실행 시작, 코드 길이: {} bytes",
-                code.len()
+                "[DenoExecutor] 실행 방식: {:?} -> {:?}",
+                options.mode, run_as
             );
-            eprintln!(
-                "[DenoExecutor] 코드 내용 (처음 200자): {}",
-                &code.chars().take(200).collect::<String>()
-            );
-
-            // ES 모듈 import 구문이 있는지 확인
-            let has_import = code.contains("import ") || code.contains("export ");
-            eprintln!("[DenoExecutor] ES 모듈 구문 감지: {}", has_import);
-
-            // 이벤트 루프 실행을 위한 런타임 핸들
-            let rt = tokio::runtime::Handle::current();
-
-            if has_import {
-                // ES 모듈로 실행
-                eprintln!("[DenoExecutor] ES 모듈로 실행 시도...");
-                let specifier = ModuleSpecifier::parse("file:///executejs/user_code.mjs")
-                    .map_err(|e| anyhow::anyhow!("{}", e))?;
-
-                eprintln!(
-                    "[DenoExecutor] load_main_es_module_from_code 호출: {}",
-                    specifier
-                );
-                let module_id = rt
-                    .block_on(async {
-                        js_runtime
-                            .load_main_es_module_from_code(&specifier, code.clone())
-                            .await
-                    })
-                    .map_err(|e| anyhow::anyhow!("{}", e))?;
-
-                eprintln!("[DenoExecutor] 모듈 로드 완료, ModuleId: {}", module_id);
 
-                // 모듈 평가 (비동기)
-                eprintln!("[DenoExecutor] mod_evaluate 호출...");
-                rt.block_on(async { js_runtime.mod_evaluate(module_id).await })
-                    .map_err(|e| anyhow::anyhow!("{}", e))?;
-
-                eprintln!("[DenoExecutor] mod_evaluate 완료");
-            } else {
-                // 일반 스크립트로 실행
-                eprintln!("[DenoExecutor] 일반 스크립트로 실행...");
-                let _result = js_runtime.execute_script("[executejs:user_code]", code)?;
-                eprintln!("[DenoExecutor] execute_script 완료");
+            // top-level await가 있는 스크립트는 REPL 모드로 평가
+            if run_as == RunAs::AwaitScript {
+                let rt = tokio::runtime::Handle::current();
+                return rt.block_on(run_await_script(&code, language, &options, &cancel_handle));
             }
 
-            // 이벤트 루프 실행 (Promise 처리 및 모듈 로딩 완료 대기)
-            eprintln!("[DenoExecutor] 이벤트 루프 실행 시작...");
-            rt.block_on(async { js_runtime.run_event_loop(Default::default()).await })?;
-            eprintln!("[DenoExecutor] 이벤트 루프 완료");
-
-            // 출력 버퍼에서 결과 가져오기
-            let output = output_buffer.lock().unwrap();
-            let result_text = output.get_output();
-
-            if result_text.is_empty() {
-                Ok("코드가 실행되었습니다.".to_string())
-            } else {
-                Ok(result_text)
+            let mut js_runtime = create_runtime(&options, &cancel_handle, false)?;
+            begin_output(&mut js_runtime, options.output_sink.clone());
+
+            // 타임아웃/취소 시 terminate_execution으로 중단할 수 있도록 isolate 핸들 등록
+            cancel_handle.begin(js_runtime.v8_isolate().thread_safe_handle());
+            let watchdog = Watchdog::start(&cancel_handle, options.timeout);
+            let result = run_user_code(
+                &mut js_runtime,
+                code,
+                language,
+                run_as == RunAs::Module,
+                &options.jsx,
+                &cancel_handle,
+            );
+            drop(watchdog);
+            let result = cancel_handle.resolve(result);
+
+            // 실패해도 그 전까지의 콘솔 출력은 오류와 함께 반환
+            let output = finish_output(&mut js_runtime);
+            match result {
+                Ok(value) => Ok(ExecutionResult { output, value }),
+                Err(error) => Err(PartialOutput::attach(error, output)),
             }
         })
         .await
@@ -526,76 +1001,144 @@ impl DenoExecutor {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::Mutex;
-    use tokio;
-
-    // 테스트 간 격리를 위한 락
-    static TEST_LOCK: Mutex<()> = Mutex::new(());
+    use crate::test_util::TempCache;
 
     #[tokio::test]
     async fn test_console_log() {
-        let _lock = TEST_LOCK.lock().unwrap();
         let mut executor = DenoExecutor::new().await.unwrap();
         let result = executor
             .execute_script("test.js", "console.log('Hello World');")
             .await;
         assert!(result.is_ok());
-        let output = result.unwrap();
+        let output = result.unwrap().output.to_text();
         println!("실제 출력: '{}'", output);
         assert!(output.contains("Hello World"));
     }
 
     #[tokio::test]
     async fn test_alert() {
-        let _lock = TEST_LOCK.lock().unwrap();
         let mut executor = DenoExecutor::new().await.unwrap();
         let result = executor
             .execute_script("test.js", "alert('Hello Alert');")
             .await;
         assert!(result.is_ok());
-        let output = result.unwrap();
+        let output = result.unwrap().output.to_text();
         println!("실제 출력: '{}'", output);
         assert!(output.contains("[ALERT] Hello Alert"));
     }
 
     #[tokio::test]
     async fn test_variable_assignment() {
-        let _lock = TEST_LOCK.lock().unwrap();
         let mut executor = DenoExecutor::new().await.unwrap();
         let result = executor
             .execute_script("test.js", "let a = 5; console.log(a);")
             .await;
         assert!(result.is_ok());
-        let output = result.unwrap();
+        let output = result.unwrap().output.to_text();
         println!("실제 출력: '{}'", output);
         assert!(output.contains("5"));
     }
 
     #[tokio::test]
     async fn test_calculation() {
-        let _lock = TEST_LOCK.lock().unwrap();
         let mut executor = DenoExecutor::new().await.unwrap();
         let result = executor
             .execute_script("test.js", "let a = 1; let b = 2; console.log(a + b);")
             .await;
         assert!(result.is_ok());
-        let output = result.unwrap();
+        let output = result.unwrap().output.to_text();
         println!("실제 출력: '{}'", output);
         assert!(output.contains("3"));
     }
 
     #[tokio::test]
     async fn test_syntax_error() {
-        let _lock = TEST_LOCK.lock().unwrap();
         let mut executor = DenoExecutor::new().await.unwrap();
         let result = executor.execute_script("test.js", "alert('adf'(;").await;
         // 문법 오류는 실행 실패를 반환해야 함
         assert!(result.is_err());
+
+        // 문법 오류도 에디터에서 표시할 위치를 가짐
+        let error = result.unwrap_err();
+        let Some(ExecutionError::Exception(error)) = error.downcast_ref() else {
+            panic!("ScriptError가 아님: {}", error);
+        };
+        assert_eq!(error.name.as_deref(), Some("SyntaxError"));
+        assert_eq!(
+            error.location,
+            Some(SourceLocation {
+                file: USER_CODE_SCRIPT_NAME.to_string(),
+                line: 1,
+                column: 13,
+            })
+        );
+        assert_eq!(error.source_line.as_deref(), Some("alert('adf'(;"));
+    }
+
+    #[tokio::test]
+    async fn test_script_error_stack_and_cause() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let error = executor
+            .execute_script(
+                "test.js",
+                "function fail() {\n  null.x;\n}\n\nconsole.log('before');\nfail();",
+            )
+            .await
+            .unwrap_err();
+        let Some(ExecutionError::Exception(error)) = error.downcast_ref() else {
+            panic!("ScriptError가 아님: {}", error);
+        };
+        assert_eq!(error.name.as_deref(), Some("TypeError"));
+        assert_eq!(
+            error.message,
+            "Cannot read properties of null (reading 'x')"
+        );
+        assert_eq!(
+            error.location,
+            Some(SourceLocation {
+                file: USER_CODE_SCRIPT_NAME.to_string(),
+                line: 2,
+                column: 8,
+            })
+        );
+        assert_eq!(error.source_line.as_deref(), Some("  null.x;"));
+        assert_eq!(error.frames.len(), 2);
+        assert_eq!(error.frames[0].function_name.as_deref(), Some("fail"));
+        assert_eq!(error.frames[1].function_name, None);
+        assert_eq!(error.frames[1].location.line, 6);
+        assert_eq!(
+            error.to_string(),
+            "Uncaught TypeError: Cannot read properties of null (reading 'x')\n    \
+             at fail ([executejs:user_code]:2:8)\n    \
+             at [executejs:user_code]:6:1"
+        );
+
+        let error = executor
+            .execute_script(
+                "test.js",
+                "try {\n  JSON.parse('{');\n} catch (e) {\n  throw new Error('config failed', { cause: e });\n}",
+            )
+            .await
+            .unwrap_err();
+        let Some(ExecutionError::Exception(error)) = error.downcast_ref() else {
+            panic!("ScriptError가 아님: {}", error);
+        };
+        assert_eq!(error.message, "config failed");
+        assert_eq!(
+            error.location.as_ref().map(|location| location.line),
+            Some(4)
+        );
+        let cause = error.cause.as_deref().unwrap();
+        assert_eq!(cause.name.as_deref(), Some("SyntaxError"));
+        assert_eq!(
+            cause.location.as_ref().map(|location| location.line),
+            Some(2)
+        );
+        assert_eq!(cause.source_line.as_deref(), Some("  JSON.parse('{');"));
     }
 
     #[tokio::test]
     async fn test_multiple_statements() {
-        let _lock = TEST_LOCK.lock().unwrap();
         let mut executor = DenoExecutor::new().await.unwrap();
         let result = executor
             .execute_script(
@@ -604,14 +1147,13 @@ mod tests {
             )
             .await;
         assert!(result.is_ok());
-        let output = result.unwrap();
+        let output = result.unwrap().output.to_text();
         println!("실제 출력: '{}'", output);
         assert!(output.contains("result: 8"));
     }
 
     #[tokio::test]
     async fn test_lodash_import() {
-        let _lock = TEST_LOCK.lock().unwrap();
         let mut executor = DenoExecutor::new().await.unwrap();
         let result = executor
             .execute_script(
@@ -629,9 +1171,1346 @@ mod tests {
             )
             .await;
         assert!(result.is_ok());
-        let output = result.unwrap();
+        let output = result.unwrap().output.to_text();
         println!("Lodash 테스트 출력: '{}'", output);
         // lodash가 사용 가능한지 또는 오류 메시지가 나오는지 확인
         assert!(output.contains("Lodash test:") || output.contains("Lodash not available:"));
     }
+
+    #[tokio::test]
+    #[ignore = "npm 레지스트리에서 lodash를 설치하므로 네트워크가 필요합니다"]
+    async fn test_lodash_require_from_registry() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let result = executor
+            .execute_script(
+                "test.js",
+                r#"
+                const _ = require('lodash');
+                const numbers = [1, 2, 3, 4, 5];
+                console.log('Lodash test:', _.map(numbers, n => n * 2));
+                "#,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.output.to_text(), "Lodash test: [ 2, 4, 6, 8, 10 ]");
+    }
+
+    #[tokio::test]
+    async fn test_session_keeps_global_scope() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let session_id = executor.create_session().await.unwrap();
+
+        executor
+            .execute_in_session(session_id, "let a = 1; const b = 2;")
+            .await
+            .unwrap();
+        let output = executor
+            .execute_in_session(session_id, "console.log(a + b);")
+            .await
+            .unwrap()
+            .output
+            .to_text();
+        assert!(output.contains("3"));
+
+        // REPL처럼 최상위 let/const 재선언 허용
+        let output = executor
+            .execute_in_session(session_id, "let a = 10; const b = 20; console.log(a + b);")
+            .await
+            .unwrap()
+            .output
+            .to_text();
+        assert!(output.contains("30"));
+
+        executor.dispose_session(session_id).unwrap();
+        assert!(executor
+            .execute_in_session(session_id, "console.log(a);")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_session_reset() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let session_id = executor.create_session().await.unwrap();
+
+        executor
+            .execute_in_session(session_id, "let counter = 1;")
+            .await
+            .unwrap();
+        executor.reset_session(session_id).await.unwrap();
+
+        let output = executor
+            .execute_in_session(session_id, "console.log(typeof counter);")
+            .await
+            .unwrap()
+            .output
+            .to_text();
+        assert!(output.contains("undefined"));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_infinite_loop() {
+        let mut executor = DenoExecutor::with_options(ExecutorOptions {
+            timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let error = executor
+            .execute_script("test.js", "while (true) {}")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::TimedOut(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_infinite_loop() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let cancel_handle = executor.cancel_handle();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            cancel_handle.cancel();
+        });
+        let error = executor
+            .execute_script("test.js", "while (true) {}")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::Cancelled)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_before_start() {
+        // 실행을 시작하기 전에 취소해도 그 실행은 바로 중단됨
+        let mut executor = DenoExecutor::new().await.unwrap();
+        executor.cancel_handle().cancel();
+        let error = executor
+            .execute_script("test.js", "while (true) {}")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::Cancelled)
+        ));
+
+        // 취소는 한 번의 실행에만 적용됨
+        let result = executor
+            .execute_script("test.js", "console.log('next');")
+            .await
+            .unwrap();
+        assert_eq!(result.output.to_text(), "next");
+    }
+
+    #[tokio::test]
+    async fn test_heap_limit_out_of_memory() {
+        let mut executor = DenoExecutor::with_options(ExecutorOptions {
+            max_heap_size: Some(32 * 1024 * 1024),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let error = executor
+            .execute_script(
+                "test.js",
+                "const chunks = []; while (true) { chunks.push(new Array(100000).fill('x')); }",
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::OutOfMemory(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_completion_value() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let result = executor
+            .execute_script("test.js", "[1, 2, 3].map(x => x * 2)")
+            .await
+            .unwrap();
+        assert!(result.output.is_empty());
+        let value = result.value.unwrap();
+        assert_eq!(value.to_string(), "[ 2, 4, 6 ]");
+        assert!(matches!(value, JsValuePreview::Array { length: 3, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_completion_value_depth_limit() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let result = executor
+            .execute_script(
+                "test.js",
+                "({ l1: { l2: { l3: { l4: { l5: { l6: 1 } } } } } })",
+            )
+            .await
+            .unwrap();
+
+        // 최상위 객체가 깊이 0이고 깊이 4(l4의 값)까지 펼침
+        let mut value = result.value.unwrap();
+        for depth in 0..=4 {
+            let JsValuePreview::Object { mut properties, .. } = value else {
+                panic!("깊이 {}의 객체를 펼치지 않음: {:?}", depth, value);
+            };
+            value = properties.remove(0).value;
+        }
+        assert_eq!(
+            value,
+            JsValuePreview::Truncated {
+                class_name: "Object".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_completion_value_awaits_promise() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let result = executor
+            .execute_script("test.js", "Promise.resolve({ answer: 42 })")
+            .await
+            .unwrap();
+        assert_eq!(result.value.unwrap().to_string(), "{ answer: 42 }");
+    }
+
+    #[tokio::test]
+    async fn test_execution_mode_detection() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+
+        // 문자열과 주석 안의 import/export는 모듈 구문이 아님
+        let result = executor
+            .execute_script(
+                "test.js",
+                "// import foo from 'foo';\nconsole.log(\"import this\");\n'export default'",
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.output.to_text(), "import this");
+        assert_eq!(result.value.unwrap().to_string(), "'export default'");
+
+        // 공백 없는 export 선언도 모듈로 실행
+        let result = executor
+            .execute_script("test.js", "export{};\n1 + 1")
+            .await
+            .unwrap();
+        assert_eq!(result.value, None);
+
+        // import.meta처럼 모듈에서만 쓸 수 있는 구문도 모듈로 실행
+        let result = executor
+            .execute_script("test.js", "console.log(typeof import.meta.url);")
+            .await
+            .unwrap();
+        assert_eq!(result.output.to_text(), "string");
+        assert_eq!(result.value, None);
+    }
+
+    #[tokio::test]
+    async fn test_top_level_await_script() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+
+        // top-level await가 있어도 스크립트처럼 마지막 표현식의 값을 반환
+        let result = executor
+            .execute_script(
+                "test.js",
+                "const value = await new Promise((resolve) => setTimeout(() => resolve(21), 10));\n\
+                 console.log('resolved', value);\nvalue * 2",
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.output.to_text(), "resolved 21");
+        assert_eq!(result.value.unwrap().to_string(), "42");
+
+        let result = executor
+            .execute_script_with_language(
+                "let total: number = 0;\n\
+                 for (const n of [1, 2, 3]) total += await Promise.resolve(n);\ntotal",
+                Language::TypeScript,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.value.unwrap().to_string(), "6");
+
+        // 예외 위치는 사용자 코드 기준
+        let error = executor
+            .execute_script("test.js", "await null;\nnull.x;")
+            .await
+            .unwrap_err();
+        let Some(ExecutionError::Exception(error)) = error.downcast_ref() else {
+            panic!("ScriptError가 아님: {}", error);
+        };
+        assert_eq!(error.name.as_deref(), Some("TypeError"));
+        assert_eq!(
+            error.location.as_ref().map(|location| location.line),
+            Some(2)
+        );
+
+        // 스크립트 모드를 지정해도 top-level await 사용 가능
+        let mut executor = DenoExecutor::with_options(ExecutorOptions {
+            mode: ExecutionMode::Script,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let result = executor
+            .execute_script("test.js", "await Promise.resolve('done')")
+            .await
+            .unwrap();
+        assert_eq!(result.value.unwrap().to_string(), "'done'");
+    }
+
+    #[tokio::test]
+    async fn test_session_top_level_await() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let session_id = executor.create_session().await.unwrap();
+
+        executor
+            .execute_in_session(session_id, "let answer = await Promise.resolve(41);")
+            .await
+            .unwrap();
+        let result = executor
+            .execute_in_session(session_id, "answer + 1")
+            .await
+            .unwrap();
+        assert_eq!(result.value.unwrap().to_string(), "42");
+    }
+
+    #[tokio::test]
+    async fn test_execution_mode_override() {
+        let mut executor = DenoExecutor::with_options(ExecutorOptions {
+            mode: ExecutionMode::Module,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let result = executor.execute_script("test.js", "1 + 1").await.unwrap();
+        assert_eq!(result.value, None);
+
+        let mut executor = DenoExecutor::with_options(ExecutorOptions {
+            mode: ExecutionMode::Script,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let error = executor
+            .execute_script("test.js", "export const x = 1;")
+            .await
+            .unwrap_err();
+        let Some(ExecutionError::Exception(error)) = error.downcast_ref() else {
+            panic!("ScriptError가 아님: {}", error);
+        };
+        assert_eq!(error.name.as_deref(), Some("SyntaxError"));
+    }
+
+    #[tokio::test]
+    async fn test_session_completion_value() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let session_id = executor.create_session().await.unwrap();
+
+        let result = executor
+            .execute_in_session(session_id, "const items = new Map([['a', 1]]); items")
+            .await
+            .unwrap();
+        assert_eq!(result.value.unwrap().to_string(), "Map(1) { 'a' => 1 }");
+
+        let result = executor
+            .execute_in_session(session_id, "items.size + 1")
+            .await
+            .unwrap();
+        assert_eq!(
+            result.value,
+            Some(JsValuePreview::Number {
+                description: "2".to_string()
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_console_entries_keep_order_and_level() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let result = executor
+            .execute_script(
+                "test.js",
+                "console.log('first');\nconsole.error('second', { code: 1 });\nconsole.warn('third');",
+            )
+            .await
+            .unwrap();
+
+        let entries = &result.output.entries;
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].level, LogLevel::Log);
+        assert_eq!(entries[1].level, LogLevel::Error);
+        assert_eq!(entries[2].level, LogLevel::Warn);
+        assert_eq!(entries[1].message, "second { code: 1 }");
+        assert!(matches!(
+            entries[1].args[1],
+            JsValuePreview::Object { ref class_name, .. } if class_name == "Object"
+        ));
+        assert_eq!(entries[1].location.as_ref().unwrap().line, 2);
+        assert_eq!(
+            result.output.to_text(),
+            "first\n[ERROR] second { code: 1 }\n[WARN] third"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_console_format_specifiers() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let result = executor
+            .execute_script(
+                "test.js",
+                "console.log('%s is %d years (%i) %j %%', 'Bob', '42.5', 7.9, { a: 1 }, 'extra');\n\
+                 console.log('%cstyled', 'color: red');",
+            )
+            .await
+            .unwrap();
+
+        let entries = &result.output.entries;
+        assert_eq!(
+            entries[0].message,
+            "Bob is 42.5 years (7) {\"a\":1} % extra"
+        );
+        assert_eq!(entries[1].message, "styled");
+        assert_eq!(entries[1].spans[0].css.as_deref(), Some("color: red"));
+    }
+
+    #[tokio::test]
+    async fn test_console_inspect_depth_and_circular() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let result = executor
+            .execute_script(
+                "test.js",
+                "const node = { name: 'root' }; node.self = node;\n\
+                 console.log(node);\n\
+                 console.log({ a: { b: { c: { d: 1 } } } });\n\
+                 console.log('%o', { a: { b: { c: { d: 1 } } } });",
+            )
+            .await
+            .unwrap();
+
+        let entries = &result.output.entries;
+        assert_eq!(
+            entries[0].message,
+            "<ref *1> { name: 'root', self: [Circular *1] }"
+        );
+        assert_eq!(entries[1].message, "{ a: { b: { c: [Object] } } }");
+        assert_eq!(entries[2].message, "{\n  a: { b: { c: { d: 1 } } }\n}");
+        assert_eq!(entries[1].spans.last().unwrap().text, " } } }");
+        assert!(entries[1]
+            .spans
+            .iter()
+            .any(|span| span.text == "[Object]" && span.style == Some(SpanStyle::Special)));
+    }
+
+    #[tokio::test]
+    async fn test_console_group_count_and_assert() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let result = executor
+            .execute_script(
+                "test.js",
+                "console.group('outer');\n\
+                 console.count();\n\
+                 console.count();\n\
+                 console.groupEnd();\n\
+                 console.assert(1 === 2, 'math is %s', 'broken');\n\
+                 console.debug('done');",
+            )
+            .await
+            .unwrap();
+
+        let entries = &result.output.entries;
+        assert_eq!(
+            entries[0].kind,
+            ConsoleEntryKind::GroupStart { collapsed: false }
+        );
+        assert_eq!(entries[1].group_depth, 1);
+        assert_eq!(entries[3].kind, ConsoleEntryKind::GroupEnd);
+        assert_eq!(entries[4].level, LogLevel::Error);
+        assert_eq!(entries[5].level, LogLevel::Debug);
+        assert_eq!(
+            result.output.to_text(),
+            "outer\n  default: 1\n  default: 2\n[ERROR] Assertion failed: math is broken\ndone"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_console_table() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let result = executor
+            .execute_script(
+                "test.js",
+                "console.table([{ a: 1, b: 'Y' }, { a: 22 }, 5]);",
+            )
+            .await
+            .unwrap();
+
+        let entry = &result.output.entries[0];
+        let ConsoleEntryKind::Table { table } = &entry.kind else {
+            panic!("표 출력이 아닙니다: {:?}", entry.kind);
+        };
+        assert_eq!(table.columns, vec!["a", "b", "Values"]);
+        assert_eq!(table.rows[1].cells[1], None);
+        assert_eq!(
+            entry.message,
+            "┌─────────┬────┬─────┬────────┐\n\
+             │ (index) │ a  │ b   │ Values │\n\
+             ├─────────┼────┼─────┼────────┤\n\
+             │ 0       │ 1  │ 'Y' │        │\n\
+             │ 1       │ 22 │     │        │\n\
+             │ 2       │    │     │ 5      │\n\
+             └─────────┴────┴─────┴────────┘"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_console_time_trace_and_dir() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let result = executor
+            .execute_script(
+                "test.js",
+                "console.time('load');\n\
+                 console.timeEnd('load');\n\
+                 console.timeEnd('load');\n\
+                 function inner() { console.trace('here'); }\n\
+                 inner();\n\
+                 console.dir('text');",
+            )
+            .await
+            .unwrap();
+
+        let entries = &result.output.entries;
+        assert!(entries[0].message.starts_with("load: "));
+        assert!(entries[0].message.ends_with("ms"));
+        assert_eq!(entries[1].level, LogLevel::Warn);
+        assert_eq!(
+            entries[1].message,
+            "No such label 'load' for console.timeEnd()"
+        );
+
+        let ConsoleEntryKind::Trace { stack } = &entries[2].kind else {
+            panic!("trace 출력이 아닙니다: {:?}", entries[2].kind);
+        };
+        assert_eq!(stack[0].function_name.as_deref(), Some("inner"));
+        assert_eq!(stack[0].location.line, 4);
+        assert!(entries[2]
+            .message
+            .starts_with("Trace: here\n    at inner ("));
+
+        assert_eq!(entries[3].message, "'text'");
+    }
+
+    #[tokio::test]
+    async fn test_output_sink_streams_before_completion() {
+        let (sink, mut receiver) = OutputSink::channel();
+        let mut executor = DenoExecutor::with_options(ExecutorOptions {
+            output_sink: Some(sink),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let execution = tokio::spawn(async move {
+            executor
+                .execute_script(
+                    "test.js",
+                    "let tick = 0;\n\
+                     const id = setInterval(() => {\n\
+                       console.log('tick', ++tick);\n\
+                       if (tick === 3) clearInterval(id);\n\
+                     }, 200);",
+                )
+                .await
+        });
+
+        // 첫 출력은 인터벌이 끝나기 전에 도착해야 함
+        let first = receiver.recv().await.unwrap();
+        assert_eq!(first.message, "tick 1");
+        assert!(!execution.is_finished());
+
+        let result = execution.await.unwrap().unwrap();
+        assert_eq!(result.output.to_text(), "tick 1\ntick 2\ntick 3");
+        assert_eq!(receiver.recv().await.unwrap().message, "tick 2");
+        assert_eq!(receiver.recv().await.unwrap().message, "tick 3");
+    }
+
+    #[tokio::test]
+    async fn test_parallel_executors_keep_output_separate() {
+        let code = |name: &str| {
+            format!(
+                "let tick = 0;\n\
+                 const id = setInterval(() => {{\n\
+                   console.log('{}', ++tick);\n\
+                   if (tick === 5) clearInterval(id);\n\
+                 }}, 10);",
+                name
+            )
+        };
+        let (first_code, second_code) = (code("first"), code("second"));
+
+        let mut first = DenoExecutor::new().await.unwrap();
+        let mut second = DenoExecutor::new().await.unwrap();
+        let (first, second) = tokio::join!(
+            first.execute_script("first.js", &first_code),
+            second.execute_script("second.js", &second_code),
+        );
+
+        let expected = |name: &str| {
+            (1..=5)
+                .map(|tick| format!("{} {}", name, tick))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        assert_eq!(first.unwrap().output.to_text(), expected("first"));
+        assert_eq!(second.unwrap().output.to_text(), expected("second"));
+    }
+
+    #[tokio::test]
+    async fn test_session_script_error() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let session_id = executor.create_session().await.unwrap();
+        executor
+            .execute_in_session(
+                session_id,
+                "function check(n) {\n  if (n < 0) throw new RangeError('negative');\n}",
+            )
+            .await
+            .unwrap();
+
+        // 이전 스니펫에서 정의한 함수가 던져도 위치는 이번 스니펫 기준
+        let error = executor
+            .execute_in_session(session_id, "check(1);\ncheck(-1);")
+            .await
+            .unwrap_err();
+        let Some(ExecutionError::Exception(error)) = error.downcast_ref() else {
+            panic!("ScriptError가 아님: {}", error);
+        };
+        assert_eq!(error.name.as_deref(), Some("RangeError"));
+        assert_eq!(error.message, "negative");
+        assert_eq!(
+            error.location.as_ref().map(|location| location.line),
+            Some(2)
+        );
+        assert_eq!(error.source_line.as_deref(), Some("check(-1);"));
+        assert_eq!(error.frames[0].function_name.as_deref(), Some("check"));
+
+        let error = executor
+            .execute_in_session(session_id, "const ok = 1;\nconst = 2;")
+            .await
+            .unwrap_err();
+        let Some(ExecutionError::Exception(error)) = error.downcast_ref() else {
+            panic!("ScriptError가 아님: {}", error);
+        };
+        assert_eq!(error.name.as_deref(), Some("SyntaxError"));
+        assert_eq!(error.source_line.as_deref(), Some("const = 2;"));
+
+        let error = executor
+            .execute_in_session(session_id, "throw 'plain';")
+            .await
+            .unwrap_err();
+        let Some(ExecutionError::Exception(error)) = error.downcast_ref() else {
+            panic!("ScriptError가 아님: {}", error);
+        };
+        assert_eq!(error.name, None);
+        assert_eq!(error.message, "plain");
+    }
+
+    #[tokio::test]
+    async fn test_output_kept_on_error() {
+        let partial_output = |error: &anyhow::Error| {
+            assert!(
+                matches!(error.downcast_ref(), Some(ExecutionError::Exception(_))),
+                "{}",
+                error
+            );
+            error
+                .downcast_ref::<PartialOutput>()
+                .map(|partial| partial.output.to_text())
+        };
+
+        // 스크립트와 ES 모듈 모두 예외 전까지의 출력을 오류와 함께 반환
+        let mut executor = DenoExecutor::new().await.unwrap();
+        for code in [
+            "console.log('before');\nthrow new Error('boom');",
+            "export {};\nconsole.log('before');\nthrow new Error('boom');",
+        ] {
+            let error = executor.execute_script("test.js", code).await.unwrap_err();
+            // 메시지는 출력을 붙이기 전과 같음
+            assert!(
+                error.to_string().starts_with("Uncaught Error: boom"),
+                "{}",
+                error
+            );
+            assert_eq!(partial_output(&error).as_deref(), Some("before"));
+        }
+
+        let session_id = executor.create_session().await.unwrap();
+        let error = executor
+            .execute_in_session(
+                session_id,
+                "console.log('before');\nthrow new Error('boom');",
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(partial_output(&error).as_deref(), Some("before"));
+    }
+
+    #[tokio::test]
+    async fn test_typescript_execution() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let result = executor
+            .execute_script(
+                "test.ts",
+                "interface User {\n  name: string;\n}\n\
+                 const user: User = { name: 'Kim' };\n\
+                 console.log((user.name as string).toUpperCase());\n\
+                 enum Color { Red, Green }\n\
+                 Color.Green",
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.output.to_text(), "KIM");
+        assert_eq!(result.value.unwrap().to_string(), "1");
+
+        // 확장자 대신 언어를 직접 지정
+        let result = executor
+            .execute_script_with_language(
+                "const double = (n: number): number => n * 2;\ndouble(21)",
+                Language::TypeScript,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.value.unwrap().to_string(), "42");
+
+        // 타입 표기는 JavaScript로 실행하면 문법 오류
+        assert!(executor
+            .execute_script("test.js", "const n: number = 1;")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_typescript_syntax_error_location() {
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let error = executor
+            .execute_script_with_language(
+                "const ok: number = 1;\nconst x: = 2;",
+                Language::TypeScript,
+            )
+            .await
+            .unwrap_err();
+        let Some(ExecutionError::Exception(error)) = error.downcast_ref() else {
+            panic!("ScriptError가 아님: {}", error);
+        };
+        assert_eq!(error.name.as_deref(), Some("SyntaxError"));
+        assert_eq!(
+            error.location.as_ref().map(|location| location.line),
+            Some(2)
+        );
+        assert_eq!(error.source_line.as_deref(), Some("const x: = 2;"));
+    }
+
+    #[tokio::test]
+    async fn test_import_typescript_module() {
+        let dir = TempCache::new();
+        let module_path = dir.file(
+            "math.ts",
+            "export type Pair = [number, number];\n\
+             export function add([a, b]: Pair): number {\n  return a + b;\n}\n",
+        );
+        let module_url = ModuleSpecifier::from_file_path(&module_path).unwrap();
+
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let result = executor
+            .execute_script(
+                "test.ts",
+                &format!(
+                    "import {{ add, type Pair }} from '{}';\n\
+                     const pair: Pair = [1, 2];\n\
+                     console.log(add(pair));",
+                    module_url
+                ),
+            )
+            .await;
+        assert_eq!(result.unwrap().output.to_text(), "3");
+    }
+
+    #[tokio::test]
+    async fn test_typescript_error_source_map() {
+        // 타입 선언이 제거되어도 오류 위치는 원래 TypeScript 코드 기준
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let error = executor
+            .execute_script_with_language(
+                "interface Point {\n  x: number;\n  y: number;\n}\n\
+                 const p: Point = { x: 1, y: 2 };\n\
+                 function fail(point: Point): never {\n  throw new Error(`bad ${point.x}`);\n}\n\
+                 fail(p);",
+                Language::TypeScript,
+            )
+            .await
+            .unwrap_err();
+        let Some(ExecutionError::Exception(error)) = error.downcast_ref() else {
+            panic!("ScriptError가 아님: {}", error);
+        };
+        assert_eq!(error.message, "bad 1");
+        assert_eq!(
+            error.location.as_ref().map(|location| location.line),
+            Some(7)
+        );
+        assert_eq!(
+            error.source_line.as_deref(),
+            Some("  throw new Error(`bad ${point.x}`);")
+        );
+        assert_eq!(error.frames[0].function_name.as_deref(), Some("fail"));
+        assert_eq!(error.frames[1].location.line, 9);
+    }
+
+    #[tokio::test]
+    async fn test_imported_module_error_source_map() {
+        let dir = TempCache::new();
+        let module_path = dir.file(
+            "check.tsx",
+            "type Props = {\n  name: string;\n};\n\n\
+             export function check(props: Props): string {\n  \
+             if (!props.name) throw new TypeError('name is required');\n  \
+             return props.name;\n}\n",
+        );
+        let module_url = ModuleSpecifier::from_file_path(&module_path).unwrap();
+
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let result = executor
+            .execute_script(
+                "test.js",
+                &format!(
+                    "import {{ check }} from '{}';\ncheck({{ name: '' }});",
+                    module_url
+                ),
+            )
+            .await;
+
+        let error = result.unwrap_err();
+        let Some(ExecutionError::Exception(error)) = error.downcast_ref() else {
+            panic!("ScriptError가 아님: {}", error);
+        };
+        assert_eq!(error.name.as_deref(), Some("TypeError"));
+        assert_eq!(error.frames[0].location.file, module_url.as_str());
+        assert_eq!(error.frames[0].location.line, 6);
+        // 사용자 코드에서는 호출한 위치를 가리킴
+        assert_eq!(
+            error.location.as_ref().map(|location| location.line),
+            Some(2)
+        );
+    }
+
+    /// 태그와 속성을 문자열로 그리는 테스트용 JSX 팩토리
+    const RENDER_TO_STRING: &str = r#"
+        function h(tag, props, ...children) {
+            if (typeof tag === 'function') return tag({ ...props, children });
+            const attrs = Object.entries(props ?? {})
+                .map(([key, value]) => ` ${key}="${value}"`)
+                .join('');
+            return `<${tag}${attrs}>${children.flat().join('')}</${tag}>`;
+        }
+        function Fragment({ children }) { return [children].flat().join(''); }
+    "#;
+
+    #[tokio::test]
+    async fn test_jsx_classic_factory() {
+        let mut executor = DenoExecutor::with_options(ExecutorOptions {
+            jsx: JsxRuntime::Classic {
+                factory: "h".to_string(),
+                fragment_factory: "Fragment".to_string(),
+            },
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let code = format!(
+            "{}\n\
+             const Greeting = ({{ name }}) => <p class=\"greeting\">Hello, {{name}}</p>;\n\
+             console.log(<div><Greeting name=\"JSX\" /><>!</></div>);",
+            RENDER_TO_STRING
+        );
+        let result = executor.execute_script("test.jsx", &code).await.unwrap();
+        assert_eq!(
+            result.output.to_text(),
+            "<div><p class=\"greeting\">Hello, JSX</p>!</div>"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tsx_with_pragma() {
+        // pragma 주석이 설정된 팩토리(기본값 React.createElement)보다 우선
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let code = format!(
+            "/** @jsx h */\n/** @jsxFrag Fragment */\n{}\n\
+             interface Props {{ items: string[] }}\n\
+             const List = ({{ items }}: Props) => <ul>{{items.map((item) => <li>{{item}}</li>)}}</ul>;\n\
+             console.log(<List items={{['a', 'b']}} />);",
+            RENDER_TO_STRING
+        );
+        let result = executor
+            .execute_script_with_language(&code, Language::Tsx)
+            .await
+            .unwrap();
+        assert_eq!(result.output.to_text(), "<ul><li>a</li><li>b</li></ul>");
+    }
+
+    #[tokio::test]
+    async fn test_jsx_automatic_runtime() {
+        // import source 아래의 jsx-runtime 모듈에서 jsx 함수를 가져와 호출
+        let dir = TempCache::new();
+        dir.file(
+            "jsx-runtime",
+            "export const Fragment = Symbol('Fragment');\n\
+             export function jsx(type, props) {\n\
+               const { children, ...rest } = props;\n\
+               return `${type}(${JSON.stringify(rest)}):${[children ?? []].flat().join(',')}`;\n\
+             }\n\
+             export const jsxs = jsx;\n",
+        );
+        let import_source = ModuleSpecifier::from_directory_path(dir.path()).unwrap();
+
+        let mut executor = DenoExecutor::with_options(ExecutorOptions {
+            jsx: JsxRuntime::Automatic {
+                import_source: import_source.as_str().trim_end_matches('/').to_string(),
+            },
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let result = executor
+            .execute_script_with_language(
+                "console.log(<span id=\"x\">{'a'}{'b'}</span>);",
+                Language::Jsx,
+            )
+            .await;
+        assert_eq!(result.unwrap().output.to_text(), r#"span({"id":"x"}):a,b"#);
+    }
+
+    #[tokio::test]
+    async fn test_install_transitive_dependencies() {
+        let cache = TempCache::new();
+        let package = |name: &str, version: &str, manifest: serde_json::Value| {
+            let dir = cache.package(name, version, &manifest.to_string());
+            cache.file(dir.join("package/index.js"), "export default 1;");
+        };
+        package(
+            "app",
+            "1.0.0",
+            serde_json::json!({
+                "dependencies": { "@scope/lib": "1.0.0", "dep": "^1.0.0", "gh": "user/repo" },
+                "peerDependencies": { "peer": "^2", "missing-peer": "^1" },
+                "peerDependenciesMeta": { "missing-peer": { "optional": true } },
+                "optionalDependencies": { "native": "^1" }
+            }),
+        );
+        package(
+            "dep",
+            "1.2.0",
+            serde_json::json!({ "dependencies": { "shared": "^1" } }),
+        );
+        package(
+            "@scope/lib",
+            "1.0.0",
+            serde_json::json!({ "dependencies": { "shared": "1.0.0" } }),
+        );
+        package("shared", "1.0.0", serde_json::json!({}));
+        package("peer", "2.0.0", serde_json::json!({}));
+
+        // 레지스트리에 접근할 수 없으므로 범위는 그래프에 있는 버전으로만 해석됨
+        let resolver = NpmResolver::with_cache_dir(
+            cache.path().to_path_buf(),
+            "http://127.0.0.1:9".to_string(),
+        )
+        .unwrap();
+        let graph = DependencyGraph::default();
+        graph.add("dep", "1.2.0");
+        graph.add("peer", "2.0.0");
+
+        let installed = resolver
+            .install_with_dependencies("app", Some("1.0.0"), &graph)
+            .await
+            .unwrap();
+        assert_eq!(installed.version, "1.0.0");
+        assert_eq!(graph.find("@scope/lib", None).as_deref(), Some("1.0.0"));
+        // `@scope/lib`가 설치한 shared@1.0.0을 dep의 `^1`에서 재사용
+        assert_eq!(graph.find("shared", Some("^1")).as_deref(), Some("1.0.0"));
+        assert_eq!(graph.find("native", None), None);
+        assert_eq!(graph.find("missing-peer", None), None);
+
+        let app_file = cache.join("app/1.0.0/package/index.js");
+        let resolve = |specifier: &str, referrer: &Path| {
+            resolver.resolve_package_import(specifier, referrer, &graph)
+        };
+        assert_eq!(
+            resolve("dep/sub/file.js", &app_file).as_deref(),
+            Some("npm:dep@1.2.0/sub/file.js")
+        );
+        assert_eq!(resolve("app", &app_file).as_deref(), Some("npm:app@1.0.0"));
+        assert_eq!(
+            resolve("shared", &cache.join("@scope/lib/1.0.0/package/index.js")).as_deref(),
+            Some("npm:shared@1.0.0")
+        );
+        assert_eq!(resolve("undeclared", &app_file), None);
+        assert_eq!(resolve("dep", Path::new("/main.js")), None);
+    }
+
+    #[tokio::test]
+    async fn test_npm_entry_is_evaluated_once() {
+        let cache = TempCache::new();
+        let dir = cache.package("counter", "1.0.0", r#"{ "main": "index.js" }"#);
+        cache.file(
+            dir.join("package/index.js"),
+            "globalThis.evaluated = (globalThis.evaluated ?? 0) + 1;\nexport const url = import.meta.url;",
+        );
+        cache.file(
+            dir.join("package/other.js"),
+            "import * as bare from 'counter';\n\
+             import * as relative from './index.js';\n\
+             export const same = bare === relative;",
+        );
+        let main = cache.file(
+            "main.js",
+            "import * as entry from 'npm:counter@1.0.0';\n\
+             import * as subpath from 'npm:counter@1.0.0/index.js';\n\
+             import { same } from 'npm:counter@1.0.0/other.js';\n\
+             const result = [entry === subpath, same, globalThis.evaluated, entry.url.startsWith('file://')];\n\
+             if (result.join(' ') !== 'true true 1 true') throw new Error(result.join(' '));",
+        );
+
+        let resolver = NpmResolver::with_cache_dir(
+            cache.path().to_path_buf(),
+            "http://127.0.0.1:9".to_string(),
+        )
+        .unwrap()
+        .with_offline(true);
+        let loader = NpmModuleLoader {
+            npm_resolver: Arc::new(Mutex::new(resolver)),
+            ..NpmModuleLoader::new().unwrap()
+        };
+        let mut runtime = JsRuntime::new(RuntimeOptions {
+            module_loader: Some(Rc::new(loader)),
+            ..Default::default()
+        });
+
+        // `npm:` specifier와 진입점 파일의 URL은 같은 모듈 (한 번만 평가)
+        let url = ModuleSpecifier::from_file_path(&main).unwrap();
+        let module_id = runtime.load_main_es_module(&url).await.unwrap();
+        let evaluation = runtime.mod_evaluate(module_id);
+        runtime.run_event_loop(Default::default()).await.unwrap();
+        evaluation.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_atomic_cache_install() {
+        use crate::npm_resolver::lock_package;
+
+        let cache = TempCache::new();
+        let resolver = NpmResolver::with_cache_dir(
+            cache.path().to_path_buf(),
+            "http://127.0.0.1:9".to_string(),
+        )
+        .unwrap();
+        let package_dir = cache.join("half").join("1.0.0");
+
+        // 압축 해제 중에 종료된 캐시(완료 표시 없음)는 사용하지 않고 다시 설치
+        cache.file(package_dir.join("package/package.json"), "{}");
+        assert!(resolver
+            .install_package("half", Some("1.0.0"))
+            .await
+            .is_err());
+
+        cache.package("half", "1.0.0", "{}");
+        let installed = resolver
+            .install_package("half", Some("1.0.0"))
+            .await
+            .unwrap();
+        assert_eq!(installed, package_dir);
+
+        // 같은 패키지의 잠금은 먼저 잡은 쪽이 놓을 때까지 대기하고, 제한 시간이 지나면 오류
+        let timeout = Duration::from_secs(5);
+        let lock = lock_package(&package_dir, timeout).await.unwrap();
+        assert!(cache.join("half/1.0.0.lock").is_file());
+        let waiting = tokio::time::timeout(
+            Duration::from_millis(300),
+            lock_package(&package_dir, timeout),
+        );
+        assert!(waiting.await.is_err());
+        let error = lock_package(&package_dir, Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("1.0.0.lock"), "{}", error);
+        drop(lock);
+        assert!(lock_package(&package_dir, timeout).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_packument_cache_revalidation() {
+        use std::io::{Read, Write};
+
+        // 첫 요청에는 ETag와 함께 응답하고, 같은 ETag로 재검증하면 304로 응답하는 레지스트리
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let registry_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::<String>::new()));
+        let received = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    let read = stream.read(&mut buffer).unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buffer[..read]);
+                }
+                let request = String::from_utf8_lossy(&request).to_lowercase();
+                // 조건부 헤더가 없어도 304로 응답하는 잘못된 프록시
+                let response = if request.contains("if-none-match: \"v1\"")
+                    || request.starts_with("get /broken-proxy ")
+                {
+                    "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n".to_string()
+                } else {
+                    let body = serde_json::json!({
+                        "name": "pkg",
+                        "dist-tags": { "latest": "1.0.0" },
+                        "versions": {
+                            "1.0.0": { "version": "1.0.0", "dist": { "tarball": "http://127.0.0.1:9/pkg.tgz" } }
+                        }
+                    })
+                    .to_string();
+                    format!(
+                        "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Type: application/vnd.npm.install-v1+json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                };
+                received.lock().unwrap().push(request);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        let cache = TempCache::new();
+        let resolver =
+            NpmResolver::with_cache_dir(cache.path().to_path_buf(), registry_url.clone())
+                .unwrap()
+                .with_metadata_ttl(Duration::ZERO);
+        let latest = |metadata: crate::npm_resolver::NpmRegistryResponse| {
+            metadata
+                .resolve_version("pkg", None)
+                .unwrap()
+                .version
+                .clone()
+        };
+
+        assert_eq!(
+            latest(resolver.fetch_metadata("pkg").await.unwrap()),
+            "1.0.0"
+        );
+        // TTL이 지나면 조건부 요청으로 재검증하고 304면 저장한 값을 사용
+        assert_eq!(
+            latest(resolver.fetch_metadata("pkg").await.unwrap()),
+            "1.0.0"
+        );
+        {
+            let requests = requests.lock().unwrap();
+            assert_eq!(requests.len(), 2);
+            assert!(requests[0].contains("accept: application/vnd.npm.install-v1+json"));
+            assert!(!requests[0].contains("if-none-match"));
+            assert!(requests[1].contains("if-none-match: \"v1\""));
+        }
+
+        // TTL 안에는 요청하지 않음
+        let cached = resolver.clone().with_metadata_ttl(DEFAULT_METADATA_TTL);
+        assert_eq!(latest(cached.fetch_metadata("pkg").await.unwrap()), "1.0.0");
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        // 1초보다 짧은 TTL도 그대로 적용
+        let short = resolver
+            .clone()
+            .with_metadata_ttl(Duration::from_millis(500));
+        short.fetch_metadata("pkg").await.unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        short.fetch_metadata("pkg").await.unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 3);
+
+        // 저장한 값이 없는데 304가 오면 파싱 오류 대신 원인을 알려 줌
+        let error = resolver.fetch_metadata("broken-proxy").await.unwrap_err();
+        assert!(error.to_string().contains("304"), "{}", error);
+
+        cached.fetch_metadata("@scope/pkg").await.unwrap();
+        assert!(cache.join(".packuments/@scope%2fpkg.json").is_file());
+
+        // 다른 레지스트리에서 받은 메타데이터는 사용하지 않음
+        let other = NpmResolver::with_cache_dir(
+            cache.path().to_path_buf(),
+            "http://127.0.0.1:9".to_string(),
+        )
+        .unwrap()
+        .with_metadata_ttl(DEFAULT_METADATA_TTL);
+        assert!(other.fetch_metadata("pkg").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_npm_offline_mode() {
+        let cache = TempCache::new();
+        for version in ["1.0.0", "1.2.0", "1.5.0", "1.9.0", "2.0.0-beta.1"] {
+            cache.package("pkg", version, "{}");
+        }
+        // 1.5.0은 설치 도중 중단된 캐시
+        fs::remove_file(cache.join("pkg/1.5.0/.complete")).unwrap();
+        // 무결성 값이 없어진 캐시는 사용하지 않음
+        fs::remove_file(cache.join("pkg/1.9.0/integrity")).unwrap();
+        // 저장해 둔 메타데이터의 latest(3.0.0)는 설치되지 않아서 사용하지 않음
+        let registry_url = "http://127.0.0.1:9";
+        cache.file(
+            ".packuments/pkg.json",
+            serde_json::json!({
+                "registry_url": registry_url,
+                "etag": null,
+                "last_modified": null,
+                "fetched_at_ms": 0,
+                "packument": { "dist-tags": { "latest": "3.0.0", "next": "2.0.0-beta.1" } }
+            })
+            .to_string(),
+        );
+
+        let resolver =
+            NpmResolver::with_cache_dir(cache.path().to_path_buf(), registry_url.to_string())
+                .unwrap()
+                .with_offline(true);
+        assert!(resolver.is_offline());
+        let install = |version: Option<&'static str>| {
+            let resolver = resolver.clone();
+            async move { resolver.install_package("pkg", version).await }
+        };
+        assert_eq!(install(None).await.unwrap(), cache.join("pkg/1.2.0"));
+        assert_eq!(
+            install(Some("^1.0.0")).await.unwrap(),
+            cache.join("pkg/1.2.0")
+        );
+        assert_eq!(
+            install(Some("~1.0")).await.unwrap(),
+            cache.join("pkg/1.0.0")
+        );
+        assert_eq!(
+            install(Some("next")).await.unwrap(),
+            cache.join("pkg/2.0.0-beta.1")
+        );
+
+        for version in [Some("1.5.0"), Some("1.9.0"), Some("^3"), Some("beta")] {
+            let error = install(version).await.unwrap_err();
+            assert!(error.to_string().contains("오프라인 모드"), "{}", error);
+        }
+        let error = resolver.install_package("missing", None).await.unwrap_err();
+        assert!(error.to_string().contains("캐시에 없습니다"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_commonjs_require() {
+        let dir = TempCache::new();
+        dir.file("helper.js", "module.exports = { sum: (a, b) => a + b };");
+        let math_path = dir.file(
+            "math.cjs",
+            "const helper = require('./helper');\n\
+             exports.add = (a, b) => helper.sum(a, b);\n\
+             module.exports.file = __filename.endsWith('math.cjs');\n\
+             Object.defineProperty(exports, 'same', { value: require('./helper.js') === helper });\n\
+             exports.count = 0;\n\
+             exports.increment = () => { exports.count += 1; };\n",
+        );
+        let data_path = dir.file("data.json", r#"{ "value": 40 }"#);
+        let cli_path = dir.file("cli.cjs", "#!/usr/bin/env node\nmodule.exports = 'cli';\n");
+        let math_url = ModuleSpecifier::from_file_path(&math_path).unwrap();
+
+        // 사용자 스크립트의 require
+        let mut executor = DenoExecutor::new().await.unwrap();
+        let result = executor
+            .execute_script(
+                "test.js",
+                &format!(
+                    "const math = require({math:?});\n\
+                     const data = require({data:?});\n\
+                     console.log(math.add(1, 1) + data.value, math.file, math.same);\n\
+                     console.log(require({math:?}) === math, {math:?} in require.cache);\n\
+                     console.log(require({cli:?}));",
+                    math = math_path.to_string_lossy(),
+                    data = data_path.to_string_lossy(),
+                    cli = cli_path.to_string_lossy(),
+                ),
+            )
+            .await
+            .unwrap();
+        // 첫 줄이 `#!`인 파일도 실행됨
+        assert_eq!(result.output.to_text(), "42 true true\ntrue true\ncli");
+
+        // ES 모듈에서 CommonJS 파일 import (default는 module.exports, 이름은 exports 할당)
+        let result = executor
+            .execute_script(
+                "test.js",
+                &format!(
+                    "import math, {{ add, same, count, increment }} from '{math_url}';\n\
+                     console.log(add(2, 3), same, typeof math.add);\n\
+                     increment();\n\
+                     console.log(count, math.count, 'value' in Object.getOwnPropertyDescriptor(math, 'count'));"
+                ),
+            )
+            .await
+            .unwrap();
+        // 가져온 뒤 exports에 대입한 값도 이름 export에 반영되고, 패키지의 속성은
+        // 데이터 속성 그대로 남음
+        assert_eq!(result.output.to_text(), "5 true function\n1 1 true");
+
+        let error = executor
+            .execute_script("test.js", "require('./missing-module.js');")
+            .await
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("Cannot find module"),
+            "{}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_require_install_failure_is_thrown_at_call() {
+        // 캐시에 없는 패키지는 오프라인 모드에서 설치에 실패함
+        let dir = TempCache::new();
+        let local = dir.file(
+            "local.cjs",
+            "try {\n  require('executejs-missing-from-local');\n} catch (error) {\n  \
+             module.exports = error.message;\n}\n",
+        );
+        let mut executor = DenoExecutor::with_options(ExecutorOptions {
+            npm_offline: true,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        // 실행 전 설치 실패는 실행을 막지 않고 그 require에서 던짐
+        let result = executor
+            .execute_script(
+                "test.js",
+                &format!(
+                    "let message = '';\n\
+                     try {{ require('executejs-missing-package'); }} \
+                     catch (error) {{ message = error.message; }}\n\
+                     if (false) {{ require('executejs-never-required'); }}\n\
+                     console.log(message.includes('executejs-missing-package'));\n\
+                     console.log(require({local:?}).includes('executejs-missing-from-local'));",
+                    local = local.to_string_lossy(),
+                ),
+            )
+            .await
+            .unwrap();
+        // 로컬 CommonJS 파일의 require는 호출할 때 설치를 시도함
+        assert_eq!(result.output.to_text(), "true\ntrue");
+    }
 }
@@ -0,0 +1,117 @@
+use crate::{JsxRuntime, Language, USER_CODE_MODULE_URL};
+use deno_ast::swc::ast::ModuleItem;
+use deno_ast::{MediaType, ParseParams, ParsedSource};
+use deno_core::ModuleSpecifier;
+use serde::{Deserialize, Serialize};
+
+/// 사용자 코드 실행 방식
+///
+/// - `Script`: 일반 스크립트로 실행 (마지막 표현식의 값을 결과로 반환)
+/// - `Module`: ES 모듈로 실행 (`import`/`export`, top-level await 사용 가능, 결과 값 없음)
+/// - `Auto`: 코드를 파싱해서 모듈 구문이 있으면 모듈, 없으면 스크립트로 실행
+///
+/// 스크립트에 top-level await가 있으면 REPL처럼 평가하므로 마지막 표현식의 값과
+/// 최상위 `let`/`const` 바인딩이 그대로 유지됩니다.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionMode {
+    #[default]
+    Auto,
+    Script,
+    Module,
+}
+
+/// 코드를 보고 결정한 실제 실행 방식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RunAs {
+    /// `execute_script`로 실행
+    Script,
+    /// top-level await가 있는 스크립트 (인스펙터 REPL 모드로 평가)
+    AwaitScript,
+    /// ES 모듈로 실행
+    Module,
+}
+
+impl ExecutionMode {
+    /// 실행 방식 결정 (`Auto`면 코드를 파싱해서 판단)
+    pub(crate) fn run_as(self, code: &str, language: Language, jsx: &JsxRuntime) -> RunAs {
+        let Ok(specifier) = ModuleSpecifier::parse(USER_CODE_MODULE_URL) else {
+            return RunAs::Script;
+        };
+        let media_type = language.media_type();
+        match self {
+            ExecutionMode::Module => RunAs::Module,
+            ExecutionMode::Script if has_top_level_await(&specifier, code, media_type) => {
+                RunAs::AwaitScript
+            }
+            ExecutionMode::Script => RunAs::Script,
+            // automatic JSX 런타임은 변환 결과에 jsx-runtime import가 추가되므로 모듈로 실행
+            ExecutionMode::Auto if language.has_jsx() && jsx.is_automatic() => RunAs::Module,
+            ExecutionMode::Auto => detect(&specifier, code, media_type),
+        }
+    }
+}
+
+/// 코드에 맞는 실행 방식 판단
+///
+/// `import`/`export` 선언이 있으면 모듈, 스크립트로 파싱되면 스크립트입니다. 문자열이나
+/// 주석 안의 `import`, 스크립트에서도 쓸 수 있는 `import()`는 모듈 구문이 아닙니다.
+/// 스크립트로는 파싱되지 않지만 async 함수 본문으로는 파싱되면 top-level await
+/// 스크립트, 그 외(`import.meta` 등)는 모듈로 판단합니다.
+fn detect(specifier: &ModuleSpecifier, code: &str, media_type: MediaType) -> RunAs {
+    // 모듈로도 파싱되지 않으면 스크립트로 실행해서 문법 오류를 그대로 보고
+    let Some(module) = parse(specifier, code, media_type, true) else {
+        return RunAs::Script;
+    };
+    let has_module_decl = module
+        .program_ref()
+        .unwrap_module()
+        .body
+        .iter()
+        .any(|item| matches!(item, ModuleItem::ModuleDecl(_)));
+
+    if has_module_decl {
+        RunAs::Module
+    } else if parse(specifier, code, media_type, false).is_some() {
+        RunAs::Script
+    } else if parses_as_async_body(specifier, code, media_type) {
+        RunAs::AwaitScript
+    } else {
+        RunAs::Module
+    }
+}
+
+/// 스크립트로는 파싱되지 않고 async 함수 본문으로는 파싱되는지 확인
+fn has_top_level_await(specifier: &ModuleSpecifier, code: &str, media_type: MediaType) -> bool {
+    parse(specifier, code, media_type, false).is_none()
+        && parses_as_async_body(specifier, code, media_type)
+}
+
+fn parses_as_async_body(specifier: &ModuleSpecifier, code: &str, media_type: MediaType) -> bool {
+    let wrapped = format!("(async () => {{\n{}\n}});", code);
+    parse(specifier, &wrapped, media_type, false).is_some()
+}
+
+/// 문법 오류 없이 파싱되면 결과 반환
+fn parse(
+    specifier: &ModuleSpecifier,
+    code: &str,
+    media_type: MediaType,
+    is_module: bool,
+) -> Option<ParsedSource> {
+    let params = ParseParams {
+        specifier: specifier.clone(),
+        text: code.into(),
+        media_type,
+        capture_tokens: false,
+        scope_analysis: false,
+        maybe_syntax: None,
+    };
+    let parsed = if is_module {
+        deno_ast::parse_module(params)
+    } else {
+        deno_ast::parse_script(params)
+    }
+    .ok()?;
+    parsed.diagnostics().is_empty().then_some(parsed)
+}
@@ -0,0 +1,430 @@
+use anyhow::{Context, Result};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 확장자 없이 import한 파일에 순서대로 붙여 보는 확장자
+const EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "json"];
+
+/// `exports`/`imports`에서 고르는 기본 조건 (`default`는 조건 목록과 관계없이 항상 일치)
+pub const DEFAULT_CONDITIONS: &[&str] = &["deno", "import", "default"];
+
+/// `exports`가 없는 패키지에서 `import`할 진입점 필드 (ESM 빌드를 가리키는 `module` 우선)
+const IMPORT_MAIN_FIELDS: &[&str] = &["module", "main"];
+
+/// `exports`가 없는 패키지에서 `require`할 진입점 필드 (`module`은 ESM 파일이라 사용하지 않음)
+const REQUIRE_MAIN_FIELDS: &[&str] = &["main"];
+
+/// `imports` 필드(`#` specifier)가 가리키는 대상
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ImportTarget {
+    /// 패키지 안의 파일
+    File(PathBuf),
+    /// 다른 패키지 (`"#dep": "other-pkg/sub"`)
+    Package(String),
+}
+
+/// `exports`/`imports` 항목에서 고른 값
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    /// 대상 경로나 패키지 (패턴이면 `*`를 치환한 값)
+    Path(String),
+    /// `null`로 막아 둔 경로
+    Excluded,
+}
+
+/// 파일이 속한 패키지 범위 (가장 가까운 상위 package.json이 있는 디렉토리)
+pub(crate) fn package_scope(path: &Path) -> Option<&Path> {
+    path.ancestors()
+        .skip(1)
+        .find(|dir| dir.join("package.json").is_file())
+}
+
+/// 패키지 루트 기준으로 진입점(`subpath`가 없을 때)이나 하위 경로의 파일 찾기
+///
+/// `exports`가 있으면 그 안에 선언된 경로만 `conditions`에 맞춰 고르고, 없으면
+/// `module`/`main` 필드, 확장자 붙이기, 디렉토리의 `index.js` 순서로 찾습니다.
+pub(crate) fn resolve_package(
+    package_root: &Path,
+    subpath: Option<&str>,
+    conditions: &[String],
+) -> Result<PathBuf> {
+    resolve_package_entry(package_root, subpath, conditions, IMPORT_MAIN_FIELDS)
+}
+
+/// `require`로 불러올 패키지 파일 찾기
+///
+/// `resolve_package`와 같지만 `exports`가 없을 때 `module` 필드는 보지 않고 `main`,
+/// `index.js` 순서로 찾습니다.
+pub(crate) fn resolve_require_package(
+    package_root: &Path,
+    subpath: Option<&str>,
+    conditions: &[String],
+) -> Result<PathBuf> {
+    resolve_package_entry(package_root, subpath, conditions, REQUIRE_MAIN_FIELDS)
+}
+
+fn resolve_package_entry(
+    package_root: &Path,
+    subpath: Option<&str>,
+    conditions: &[String],
+    main_fields: &[&str],
+) -> Result<PathBuf> {
+    let package_json = read_package_json(package_root)?;
+    let key = subpath.map_or_else(|| ".".to_string(), |subpath| format!("./{}", subpath));
+
+    let exports = &package_json["exports"];
+    if !exports.is_null() {
+        let Some(Target::Path(target)) = exports_target(exports, &key, conditions) else {
+            anyhow::bail!("패키지의 exports에 선언되지 않은 경로입니다: {}", key);
+        };
+        let path = package_target(package_root, &target)
+            .ok_or_else(|| anyhow::anyhow!("잘못된 exports 대상입니다: {}", target))?;
+        return if path.is_file() {
+            Ok(path)
+        } else {
+            Err(anyhow::anyhow!("exports 대상 파일이 없습니다: {:?}", path))
+        };
+    }
+
+    let resolved = match subpath {
+        Some(subpath) => resolve_file(&package_root.join(subpath)),
+        None => main_fields
+            .iter()
+            .filter_map(|field| package_json[*field].as_str())
+            .find_map(|main| {
+                let main = package_root.join(main);
+                load_as_file(&main).or_else(|| load_index(&main))
+            })
+            .or_else(|| load_index(package_root)),
+    };
+    resolved.ok_or_else(|| anyhow::anyhow!("패키지에서 모듈을 찾을 수 없습니다: {}", key))
+}
+
+/// 패키지의 `imports` 필드로 `#` specifier 해석
+pub(crate) fn resolve_imports(
+    specifier: &str,
+    referrer_path: &Path,
+    conditions: &[String],
+) -> Result<ImportTarget> {
+    let scope = package_scope(referrer_path)
+        .ok_or_else(|| anyhow::anyhow!("package.json을 찾을 수 없습니다: {:?}", referrer_path))?;
+    let package_json = read_package_json(scope)?;
+    let target = package_json["imports"]
+        .as_object()
+        .and_then(|imports| match_key(imports, specifier, conditions));
+    let Some(Target::Path(target)) = target else {
+        anyhow::bail!("패키지의 imports에 선언되지 않은 경로입니다: {}", specifier);
+    };
+
+    if target.starts_with("./") {
+        package_target(scope, &target)
+            .map(ImportTarget::File)
+            .ok_or_else(|| anyhow::anyhow!("잘못된 imports 대상입니다: {}", target))
+    } else {
+        Ok(ImportTarget::Package(target))
+    }
+}
+
+/// 파일 경로에 확장자를 붙여 보고, 디렉토리면 `main`이나 `index.js` 찾기
+pub(crate) fn resolve_file(path: &Path) -> Option<PathBuf> {
+    load_as_file(path).or_else(|| load_as_directory(path))
+}
+
+fn load_as_file(path: &Path) -> Option<PathBuf> {
+    if path.is_file() {
+        return Some(path.to_path_buf());
+    }
+    EXTENSIONS.iter().find_map(|extension| {
+        let mut candidate = OsString::from(path.as_os_str());
+        candidate.push(".");
+        candidate.push(extension);
+        let candidate = PathBuf::from(candidate);
+        candidate.is_file().then_some(candidate)
+    })
+}
+
+fn load_as_directory(dir: &Path) -> Option<PathBuf> {
+    if !dir.is_dir() {
+        return None;
+    }
+    let main = read_package_json(dir)
+        .ok()
+        .and_then(|package_json| package_json["main"].as_str().map(|main| dir.join(main)));
+    main.and_then(|main| load_as_file(&main).or_else(|| load_index(&main)))
+        .or_else(|| load_index(dir))
+}
+
+fn load_index(dir: &Path) -> Option<PathBuf> {
+    load_as_file(&dir.join("index"))
+}
+
+/// `exports`에서 하위 경로(`.` 또는 `./sub`)의 대상 선택
+///
+/// 문자열, 배열, 조건 객체만 있으면 `.`에 대한 값으로 봅니다.
+fn exports_target(exports: &serde_json::Value, key: &str, conditions: &[String]) -> Option<Target> {
+    match exports.as_object() {
+        Some(map) if map.keys().any(|k| k.starts_with('.')) => match_key(map, key, conditions),
+        _ if key == "." => select_target(exports, None, conditions),
+        _ => None,
+    }
+}
+
+/// `exports`/`imports` 맵에서 `key`에 해당하는 항목의 대상 선택
+///
+/// 같은 키가 있으면 그 항목을, 없으면 `*` 패턴 중 `*` 앞부분이 가장 긴(같으면 키가 긴)
+/// 항목을 사용하고 `*`에 일치한 부분을 대상의 `*`에 넣습니다.
+fn match_key(
+    map: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+    conditions: &[String],
+) -> Option<Target> {
+    if let Some(value) = map.get(key).filter(|_| !key.contains('*')) {
+        return select_target(value, None, conditions);
+    }
+    let (pattern, matched) = map
+        .keys()
+        .filter_map(|pattern| {
+            let (base, trailer) = pattern.split_once('*')?;
+            if trailer.contains('*') {
+                return None;
+            }
+            let matched = key.strip_prefix(base)?.strip_suffix(trailer)?;
+            (!matched.is_empty()).then_some((pattern, matched))
+        })
+        .max_by_key(|(pattern, _)| (pattern.find('*'), pattern.len()))?;
+    select_target(&map[pattern], Some(matched), conditions)
+}
+
+/// 조건 객체와 배열에서 대상 선택
+///
+/// 조건 객체는 선언된 순서대로 보고 `conditions`에 있거나 `default`인 첫 조건을 따릅니다.
+/// 일치하는 조건이 없으면 `None`, `null`이면 `Target::Excluded`입니다.
+fn select_target(
+    value: &serde_json::Value,
+    matched: Option<&str>,
+    conditions: &[String],
+) -> Option<Target> {
+    match value {
+        serde_json::Value::String(target) => Some(Target::Path(match matched {
+            Some(matched) => target.replace('*', matched),
+            None => target.clone(),
+        })),
+        serde_json::Value::Null => Some(Target::Excluded),
+        serde_json::Value::Array(targets) => targets
+            .iter()
+            .find_map(|target| select_target(target, matched, conditions)),
+        serde_json::Value::Object(map) => map
+            .iter()
+            .filter(|(condition, _)| {
+                *condition == "default" || conditions.iter().any(|c| c == *condition)
+            })
+            .find_map(|(_, target)| select_target(target, matched, conditions)),
+        _ => None,
+    }
+}
+
+/// `./`로 시작하는 대상을 패키지 루트 기준 경로로 변환 (`..`으로 루트를 벗어나면 거부)
+fn package_target(package_root: &Path, target: &str) -> Option<PathBuf> {
+    let relative = target.strip_prefix("./")?;
+    if relative.split('/').any(|segment| segment == "..") {
+        return None;
+    }
+    Some(package_root.join(relative))
+}
+
+fn read_package_json(dir: &Path) -> Result<serde_json::Value> {
+    let content =
+        fs::read_to_string(dir.join("package.json")).context("package.json을 읽을 수 없습니다")?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempCache;
+
+    #[test]
+    fn test_node_module_resolution() {
+        let conditions: Vec<String> = DEFAULT_CONDITIONS.iter().map(|c| c.to_string()).collect();
+        let root = TempCache::new();
+        root.file(
+            "exported/package.json",
+            r##"{
+                "exports": {
+                    ".": { "import": "./esm/index.js", "require": "./cjs/index.js" },
+                    "./feature": "./esm/feature.js",
+                    "./internal": null
+                },
+                "imports": { "#util": "./src/util.js", "#dep": "dep/sub" }
+            }"##,
+        );
+        let esm_index = root.file("exported/esm/index.js", "");
+        let feature = root.file("exported/esm/feature.js", "");
+        let util = root.file("exported/src/util.js", "");
+        root.file("exported/internal.js", "");
+        root.file("plain/package.json", r#"{ "main": "lib/main" }"#);
+        let main = root.file("plain/lib/main.js", "");
+        let utils_index = root.file("plain/utils/index.js", "");
+        let cjs = root.file("plain/legacy.cjs", "");
+
+        let exported = root.join("exported");
+        assert_eq!(
+            resolve_package(&exported, None, &conditions).unwrap(),
+            esm_index
+        );
+        assert_eq!(
+            resolve_package(&exported, Some("feature"), &conditions).unwrap(),
+            feature
+        );
+        // exports가 있으면 선언되지 않은 경로는 가져올 수 없음
+        assert!(resolve_package(&exported, Some("internal"), &conditions).is_err());
+        assert!(resolve_package(&exported, Some("esm/index.js"), &conditions).is_err());
+
+        let plain = root.join("plain");
+        assert_eq!(resolve_package(&plain, None, &conditions).unwrap(), main);
+        assert_eq!(
+            resolve_package(&plain, Some("utils"), &conditions).unwrap(),
+            utils_index
+        );
+        assert_eq!(
+            resolve_package(&plain, Some("legacy"), &conditions).unwrap(),
+            cjs
+        );
+        assert!(resolve_package(&plain, Some("missing"), &conditions).is_err());
+        assert_eq!(resolve_file(&plain.join("lib/main")), Some(main));
+
+        assert_eq!(
+            resolve_imports("#util", &esm_index, &conditions).unwrap(),
+            ImportTarget::File(util)
+        );
+        assert_eq!(
+            resolve_imports("#dep", &feature, &conditions).unwrap(),
+            ImportTarget::Package("dep/sub".to_string())
+        );
+        assert!(resolve_imports("#missing", &esm_index, &conditions).is_err());
+    }
+
+    #[test]
+    fn test_require_skips_module_field() {
+        // redux 4처럼 `module`은 ESM 빌드, `main`은 CommonJS 빌드를 가리키는 패키지
+        let root = TempCache::new();
+        root.file(
+            "dual/package.json",
+            r#"{ "main": "lib/index.js", "module": "es/index.js" }"#,
+        );
+        let esm = root.file("dual/es/index.js", "export default 1;");
+        let cjs = root.file("dual/lib/index.js", "module.exports = 1;");
+        root.file("bare/package.json", r#"{ "module": "es/index.js" }"#);
+        root.file("bare/es/index.js", "export default 1;");
+        let index = root.file("bare/index.js", "module.exports = 1;");
+        root.file(
+            "exported/package.json",
+            r#"{
+                "module": "es/index.js",
+                "exports": { ".": { "import": "./es/index.js", "require": "./lib/index.cjs" } }
+            }"#,
+        );
+        let exported_cjs = root.file("exported/lib/index.cjs", "module.exports = 1;");
+        root.file("exported/es/index.js", "export default 1;");
+
+        let conditions: Vec<String> = ["require", "default"].map(String::from).to_vec();
+        let dual = root.join("dual");
+        assert_eq!(resolve_package(&dual, None, &conditions).unwrap(), esm);
+        assert_eq!(
+            resolve_require_package(&dual, None, &conditions).unwrap(),
+            cjs
+        );
+        // main이 없으면 module 대신 index.js
+        assert_eq!(
+            resolve_require_package(&root.join("bare"), None, &conditions).unwrap(),
+            index
+        );
+        assert_eq!(
+            resolve_require_package(&root.join("exported"), None, &conditions).unwrap(),
+            exported_cjs
+        );
+    }
+
+    #[test]
+    fn test_package_exports_conditions() {
+        let dir = TempCache::new();
+        let root = dir.path();
+        dir.file(
+            "package.json",
+            r#"{
+                "exports": {
+                    ".": {
+                        "types": "./index.d.ts",
+                        "browser": { "import": "./browser.mjs", "require": "./browser.cjs" },
+                        "node": "./node.mjs",
+                        "default": "./index.mjs"
+                    },
+                    "./*": "./dist/*.mjs",
+                    "./utils/*": { "import": "./dist/utils/*/index.mjs" },
+                    "./utils/private/*": null,
+                    "./package.json": "./package.json"
+                }
+            }"#,
+        );
+        for file in [
+            "index.mjs",
+            "browser.mjs",
+            "node.mjs",
+            "dist/addDays.mjs",
+            "dist/utils/format/index.mjs",
+            "dist/utils/private/secret/index.mjs",
+        ] {
+            dir.file(file, "");
+        }
+        let resolve = |subpath: Option<&str>, conditions: &[&str]| {
+            let conditions: Vec<String> = conditions.iter().map(|c| c.to_string()).collect();
+            resolve_package(root, subpath, &conditions)
+                .ok()
+                .map(|path| {
+                    path.strip_prefix(root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .into_owned()
+                })
+        };
+
+        // 조건은 package.json에 선언된 순서대로 보고, default는 항상 일치
+        assert_eq!(
+            resolve(None, &["deno", "import"]).as_deref(),
+            Some("index.mjs")
+        );
+        assert_eq!(
+            resolve(None, &["import", "browser"]).as_deref(),
+            Some("browser.mjs")
+        );
+        assert_eq!(
+            resolve(None, &["node", "browser", "import"]).as_deref(),
+            Some("browser.mjs")
+        );
+        assert_eq!(
+            resolve(None, &["node", "import"]).as_deref(),
+            Some("node.mjs")
+        );
+        // browser 조건 안에 맞는 조건이 없으면 다음 조건으로 넘어감
+        assert_eq!(resolve(None, &["browser"]).as_deref(), Some("index.mjs"));
+
+        // 하위 경로 패턴은 `*` 앞부분이 가장 긴 패턴을 사용
+        assert_eq!(
+            resolve(Some("addDays"), &["import"]).as_deref(),
+            Some("dist/addDays.mjs")
+        );
+        assert_eq!(
+            resolve(Some("utils/format"), &["import"]).as_deref(),
+            Some("dist/utils/format/index.mjs")
+        );
+        assert_eq!(resolve(Some("utils/format"), &["require"]), None);
+        // null로 막은 경로는 더 짧은 패턴에 일치해도 가져올 수 없음
+        assert_eq!(resolve(Some("utils/private/secret"), &["import"]), None);
+        assert_eq!(resolve(Some("missing"), &["import"]), None);
+        assert_eq!(
+            resolve(Some("package.json"), &["import"]).as_deref(),
+            Some("package.json")
+        );
+    }
+}
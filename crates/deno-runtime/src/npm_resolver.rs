@@ -1,38 +1,287 @@
+use crate::integrity::{Integrity, INTEGRITY_FILE};
+use crate::node_resolver;
+use crate::npmrc::{NpmrcConfig, DEFAULT_REGISTRY};
+use crate::packument::{PackumentCache, DEFAULT_METADATA_TTL};
+use crate::semver::{Version, VersionReq};
 use anyhow::{Context, Result};
+use reqwest::header::AUTHORIZATION;
 use serde::Deserialize;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
 use std::io::Read;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// 압축 해제와 무결성 값 기록이 모두 끝난 패키지 디렉토리에만 있는 완료 표시 파일
+const COMPLETE_MARKER: &str = ".complete";
+
+/// 다른 실행이 같은 패키지를 설치하는 동안 잠금을 기다리는 최대 시간
+const PACKAGE_LOCK_TIMEOUT: Duration = Duration::from_secs(5 * 60);
 
 /// npm 레지스트리 메타데이터 응답
 #[derive(Debug, Deserialize)]
-struct NpmRegistryResponse {
-    #[serde(rename = "dist-tags")]
-    dist_tags: DistTags,
-    versions: serde_json::Value,
+pub(crate) struct NpmRegistryResponse {
+    /// `latest`, `next`, `beta` 같은 태그와 가리키는 버전
+    #[serde(rename = "dist-tags", default)]
+    dist_tags: HashMap<String, String>,
+    #[serde(default)]
+    versions: HashMap<String, PackageVersion>,
 }
 
-#[derive(Debug, Deserialize)]
-struct DistTags {
-    latest: String,
+impl NpmRegistryResponse {
+    /// 요청한 버전(정확한 버전, 범위, dist-tag)에 맞는 버전 선택
+    ///
+    /// 범위는 npm과 같이 `latest` 태그가 범위를 만족하면 그 버전을, 아니면 범위를 만족하는
+    /// 가장 높은 버전을 고릅니다.
+    pub(crate) fn resolve_version(
+        &self,
+        package_name: &str,
+        requested: Option<&str>,
+    ) -> Result<&PackageVersion> {
+        let requested = requested
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .unwrap_or("latest");
+        let not_found = || {
+            anyhow::anyhow!(
+                "패키지 버전을 찾을 수 없습니다: {}@{}",
+                package_name,
+                requested
+            )
+        };
+
+        // dist-tag (`latest`, `next`, `beta` 등)
+        if let Some(tagged) = self.dist_tags.get(requested) {
+            return self.versions.get(tagged).ok_or_else(not_found);
+        }
+        // 정확한 버전
+        if let Some(exact) = self.versions.get(requested) {
+            return Ok(exact);
+        }
+
+        let range = VersionReq::parse(requested).ok_or_else(not_found)?;
+        let latest = self
+            .dist_tags
+            .get("latest")
+            .filter(|latest| Version::parse(latest).is_some_and(|v| range.matches(&v)));
+        let version = match latest {
+            Some(latest) => latest.as_str(),
+            None => range
+                .max_satisfying(self.versions.keys().map(String::as_str))
+                .ok_or_else(not_found)?,
+        };
+        self.versions.get(version).ok_or_else(not_found)
+    }
 }
 
 /// 패키지 버전 메타데이터
 #[derive(Debug, Deserialize)]
-struct PackageVersion {
-    version: String,
+pub(crate) struct PackageVersion {
+    pub version: String,
     dist: Dist,
 }
 
+impl PackageVersion {
+    /// 캐시에 설치된 버전 (오프라인 모드에서는 다운로드하지 않으므로 tarball 정보가 없음)
+    fn cached(version: &str) -> Self {
+        Self {
+            version: version.to_string(),
+            dist: Dist {
+                tarball: String::new(),
+                integrity: None,
+                shasum: None,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct Dist {
     tarball: String,
+    /// SRI 형식 해시 (예: `sha512-...`)
+    #[serde(default)]
+    integrity: Option<String>,
+    /// 16진수 sha1 해시 (오래된 패키지는 이것만 있음)
+    #[serde(default)]
+    shasum: Option<String>,
+}
+
+impl Dist {
+    fn integrity(&self) -> Result<Integrity> {
+        Integrity::new(self.integrity.as_deref(), self.shasum.as_deref())
+    }
+}
+
+/// `npm:` 뒤의 패키지 지정자를 이름, 버전, 하위 경로로 분리
+///
+/// 예: `@scope/pkg@1.0.0/sub/path` → (`@scope/pkg`, `1.0.0`, `sub/path`)
+pub(crate) fn parse_package_specifier(spec: &str) -> (String, Option<String>, Option<String>) {
+    // 스코프 패키지는 두 번째 세그먼트까지가 패키지 이름
+    let name_start = if spec.starts_with('@') {
+        spec.find('/').map_or(spec.len(), |slash| slash + 1)
+    } else {
+        0
+    };
+    let (name_and_version, subpath) = match spec[name_start..].find('/') {
+        Some(slash) => (
+            &spec[..name_start + slash],
+            Some(&spec[name_start + slash + 1..]),
+        ),
+        None => (spec, None),
+    };
+
+    // 스코프의 '@'는 건너뛰고 버전 구분자 찾기
+    let (name, version) = match name_and_version.get(1..).and_then(|rest| rest.find('@')) {
+        Some(at) => (
+            &name_and_version[..at + 1],
+            Some(&name_and_version[at + 2..]),
+        ),
+        None => (name_and_version, None),
+    };
+
+    (
+        name.to_string(),
+        version.filter(|v| !v.is_empty()).map(str::to_string),
+        subpath.filter(|p| !p.is_empty()).map(str::to_string),
+    )
+}
+
+/// 캐시에 설치된 패키지
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledPackage {
+    pub name: String,
+    pub version: String,
+    /// `캐시/<이름>/<버전>` 디렉토리 (패키지 내용은 그 아래 `package/`)
+    pub dir: PathBuf,
+}
+
+impl InstalledPackage {
+    /// 설치가 끝까지 완료된 캐시인지 (완료 표시 파일은 교체 직전에 마지막으로 기록)
+    fn is_complete(&self) -> bool {
+        self.dir.join(COMPLETE_MARKER).is_file()
+    }
+
+    /// 완료된 캐시이고, 저장된 무결성 값이 레지스트리가 게시한 해시와 같은지
+    fn is_cached(&self, integrity: &Integrity) -> bool {
+        self.is_complete()
+            && fs::read_to_string(self.dir.join(INTEGRITY_FILE))
+                .is_ok_and(|stored| integrity.matches(&stored))
+    }
+}
+
+/// package.json에 선언된 의존성 하나
+#[derive(Debug, Clone)]
+struct Dependency {
+    name: String,
+    range: String,
+    /// `peerDependencies` (이미 설치된 버전이 범위를 만족하면 새로 설치하지 않음)
+    peer: bool,
+    /// `optionalDependencies`나 선택적 peer (설치에 실패해도 무시)
+    optional: bool,
+}
+
+/// 패키지의 package.json에서 `dependencies`/`peerDependencies`/`optionalDependencies` 읽기
+fn read_dependencies(package_dir: &Path) -> Result<Vec<Dependency>> {
+    let package_json_path = package_dir.join("package").join("package.json");
+    let package_json: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(&package_json_path).context("package.json을 읽을 수 없습니다")?,
+    )?;
+
+    let ranges = |field: &str| {
+        package_json[field]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, range)| Some((name.clone(), range.as_str()?.to_string())))
+            .collect::<Vec<_>>()
+    };
+    let optional_peers: HashSet<&str> = package_json["peerDependenciesMeta"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(_, meta)| meta["optional"].as_bool() == Some(true))
+        .map(|(name, _)| name.as_str())
+        .collect();
+
+    // 같은 이름이 여러 필드에 있으면 optionalDependencies가 우선 (npm과 동일)
+    let mut dependencies: Vec<Dependency> = Vec::new();
+    let mut push = |name: String, range: String, peer: bool, optional: bool| {
+        dependencies.retain(|dependency| dependency.name != name);
+        dependencies.push(Dependency {
+            name,
+            range,
+            peer,
+            optional,
+        });
+    };
+    for (name, range) in ranges("peerDependencies") {
+        let optional = optional_peers.contains(name.as_str());
+        push(name, range, true, optional);
+    }
+    for (name, range) in ranges("dependencies") {
+        push(name, range, false, false);
+    }
+    for (name, range) in ranges("optionalDependencies") {
+        push(name, range, false, true);
+    }
+    Ok(dependencies)
+}
+
+/// 레지스트리에서 받을 수 있는 의존성인지 (`npm:` 별칭, `git+`, `file:`, URL, GitHub 축약형 제외)
+fn is_registry_range(range: &str) -> bool {
+    !range.contains(':') && !range.contains('/')
+}
+
+/// 한 런타임에서 설치한 패키지 버전 (이름 → 버전 목록)
+///
+/// 의존성을 설치할 때 이미 설치한 버전이 범위를 만족하면 재사용해서, 같은 패키지가
+/// 여러 버전으로 중복 설치되거나 peer 의존성이 서로 다른 인스턴스를 보지 않게 합니다.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph(Arc<Mutex<HashMap<String, Vec<String>>>>);
+
+impl DependencyGraph {
+    /// 설치된 버전 중 요청(정확한 버전이나 범위, 없으면 아무 버전)을 만족하는 가장 높은 버전
+    pub fn find(&self, name: &str, requested: Option<&str>) -> Option<String> {
+        let packages = self.0.lock().unwrap();
+        let versions = packages.get(name)?;
+        let range = VersionReq::parse(requested.unwrap_or("*"))?;
+        range
+            .max_satisfying(versions.iter().map(String::as_str))
+            .map(str::to_string)
+    }
+
+    pub fn add(&self, name: &str, version: &str) {
+        let mut packages = self.0.lock().unwrap();
+        let versions = packages.entry(name.to_string()).or_default();
+        if !versions.iter().any(|v| v == version) {
+            versions.push(version.to_string());
+        }
+    }
+}
+
+fn default_conditions() -> Vec<String> {
+    node_resolver::DEFAULT_CONDITIONS
+        .iter()
+        .map(|condition| condition.to_string())
+        .collect()
 }
 
 /// npm 패키지 리졸버
+#[derive(Debug, Clone)]
 pub struct NpmResolver {
     cache_dir: PathBuf,
     registry_url: String,
+    /// package.json `exports`/`imports`에서 고를 조건
+    conditions: Vec<String>,
+    /// 캐시한 레지스트리 메타데이터를 재검증 없이 사용하는 시간
+    metadata_ttl: Duration,
+    /// 레지스트리에 요청하지 않고 캐시에 설치된 패키지만 사용
+    offline: bool,
+    /// 스코프별 레지스트리와 인증 정보
+    npmrc: NpmrcConfig,
+    /// `npmrc`의 TLS 설정을 적용한 클라이언트
+    client: reqwest::Client,
 }
 
 impl NpmResolver {
@@ -43,13 +292,20 @@ impl NpmResolver {
             .join("executejs")
             .join("npm");
 
-        // 캐시 디렉토리 생성
-        fs::create_dir_all(&cache_dir).context("캐시 디렉토리를 생성할 수 없습니다")?;
+        let resolver = Self::with_cache_dir(cache_dir, DEFAULT_REGISTRY.to_string())?;
 
-        Ok(Self {
-            cache_dir,
-            registry_url: "https://registry.npmjs.org".to_string(),
-        })
+        // 프로젝트/사용자/전역 .npmrc의 레지스트리와 인증 정보 적용 (잘못된 설정 때문에
+        // npm import 전체가 막히지 않도록 실패하면 기본 설정 사용)
+        match NpmrcConfig::load().and_then(|npmrc| resolver.clone().with_npmrc(npmrc)) {
+            Ok(resolver) => Ok(resolver),
+            Err(e) => {
+                eprintln!(
+                    "[NpmResolver::new] .npmrc 설정 실패 (기본 설정 사용): {:#}",
+                    e
+                );
+                Ok(resolver)
+            }
+        }
     }
 
     /// 캐시 디렉토리와 레지스트리 URL을 지정하여 생성
@@ -60,9 +316,64 @@ impl NpmResolver {
         Ok(Self {
             cache_dir,
             registry_url,
+            conditions: default_conditions(),
+            metadata_ttl: DEFAULT_METADATA_TTL,
+            offline: false,
+            npmrc: NpmrcConfig::default(),
+            client: reqwest::Client::new(),
         })
     }
 
+    /// `.npmrc` 설정 적용 (`registry=`가 있으면 기본 레지스트리도 바뀜)
+    ///
+    /// `@scope/pkg`는 스코프에 지정한 레지스트리에서 받고, 메타데이터와 tarball 요청에는
+    /// URL에 맞는 인증 정보를 붙입니다. `cafile`을 읽을 수 없으면 오류를 반환합니다.
+    pub fn with_npmrc(mut self, npmrc: NpmrcConfig) -> Result<Self> {
+        self.client = npmrc.http_client()?;
+        if let Some(registry) = npmrc.registry() {
+            self.registry_url = registry.to_string();
+        }
+        self.npmrc = npmrc;
+        Ok(self)
+    }
+
+    /// package.json `exports`/`imports` 조건 지정 (예: `deno`, `browser`, `node`, `import`)
+    ///
+    /// 조건 객체는 선언된 순서대로 보고 이 목록에 있는 첫 조건을 고릅니다. `default`는
+    /// 목록에 없어도 항상 일치합니다.
+    pub fn with_conditions(mut self, conditions: Vec<String>) -> Self {
+        self.conditions = conditions;
+        self
+    }
+
+    /// package.json `exports`/`imports`에서 고를 조건
+    pub fn conditions(&self) -> &[String] {
+        &self.conditions
+    }
+
+    /// 캐시한 레지스트리 메타데이터를 재검증 없이 사용하는 시간 지정 (기본 5분)
+    ///
+    /// 시간이 지나면 `ETag`/`Last-Modified`로 변경 여부만 확인합니다. `Duration::ZERO`면
+    /// 매번 재검증합니다.
+    pub fn with_metadata_ttl(mut self, ttl: Duration) -> Self {
+        self.metadata_ttl = ttl;
+        self
+    }
+
+    /// 오프라인 모드 지정
+    ///
+    /// 레지스트리에 요청하지 않고 버전 범위와 dist-tag를 캐시에 설치된 버전과 저장해 둔
+    /// 메타데이터로만 해석합니다. 캐시에 없는 패키지나 버전은 오류가 됩니다.
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// 오프라인 모드인지
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
     /// 캐시 디렉토리 경로 반환
     pub fn cache_dir(&self) -> &Path {
         &self.cache_dir
@@ -73,56 +384,162 @@ impl NpmResolver {
         &self.registry_url
     }
 
+    /// 패키지를 받을 레지스트리 (스코프에 지정한 레지스트리가 없으면 기본 레지스트리)
+    pub fn registry_url_for(&self, package_name: &str) -> &str {
+        package_name
+            .split_once('/')
+            .filter(|(scope, _)| scope.starts_with('@'))
+            .and_then(|(scope, _)| self.npmrc.scope_registry(scope))
+            .unwrap_or(&self.registry_url)
+    }
+
     /// 패키지 다운로드 및 설치
     pub async fn install_package(
         &self,
         package_name: &str,
         version: Option<&str>,
     ) -> Result<PathBuf> {
+        Ok(self.install(package_name, version).await?.dir)
+    }
+
+    /// 패키지와 의존성 전체를 설치
+    ///
+    /// `dependencies`, `peerDependencies`, `optionalDependencies`를 너비 우선으로 따라가며
+    /// 설치하고, `graph`에 이미 범위를 만족하는 버전이 있으면 그 버전을 재사용합니다.
+    /// 선택적 의존성과 레지스트리 범위가 아닌 의존성(`git+`, `file:` 등)은 건너뜁니다.
+    pub async fn install_with_dependencies(
+        &self,
+        package_name: &str,
+        version: Option<&str>,
+        graph: &DependencyGraph,
+    ) -> Result<InstalledPackage> {
+        let root = self.install_in_graph(package_name, version, graph).await?;
+
+        let mut visited = HashSet::new();
+        visited.insert((root.name.clone(), root.version.clone()));
+        let mut queue = VecDeque::from([root.clone()]);
+        while let Some(package) = queue.pop_front() {
+            for dependency in read_dependencies(&package.dir)? {
+                if dependency.peer
+                    && graph
+                        .find(&dependency.name, Some(&dependency.range))
+                        .is_some()
+                {
+                    continue;
+                }
+                if !is_registry_range(&dependency.range) {
+                    eprintln!(
+                        "[NpmResolver::install_with_dependencies] 지원하지 않는 의존성 건너뜀: {}@{}",
+                        dependency.name, dependency.range
+                    );
+                    continue;
+                }
+
+                match self
+                    .install_in_graph(&dependency.name, Some(&dependency.range), graph)
+                    .await
+                {
+                    Ok(installed) => {
+                        if visited.insert((installed.name.clone(), installed.version.clone())) {
+                            queue.push_back(installed);
+                        }
+                    }
+                    Err(e) if dependency.optional => {
+                        eprintln!(
+                            "[NpmResolver::install_with_dependencies] 선택적 의존성 설치 실패 (무시): {}@{}: {}",
+                            dependency.name, dependency.range, e
+                        );
+                    }
+                    Err(e) => {
+                        return Err(e.context(format!(
+                            "{}@{}의 의존성 {}@{} 설치 실패",
+                            package.name, package.version, dependency.name, dependency.range
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(root)
+    }
+
+    /// 그래프에 범위를 만족하는 버전이 있으면 재사용하고, 없으면 설치해서 그래프에 추가
+    async fn install_in_graph(
+        &self,
+        package_name: &str,
+        version: Option<&str>,
+        graph: &DependencyGraph,
+    ) -> Result<InstalledPackage> {
+        let reused = graph.find(package_name, version);
+        let installed = self
+            .install(package_name, reused.as_deref().or(version))
+            .await?;
+        graph.add(&installed.name, &installed.version);
+        Ok(installed)
+    }
+
+    /// 패키지 버전을 결정하고 캐시에 없으면 다운로드해서 설치
+    async fn install(&self, package_name: &str, version: Option<&str>) -> Result<InstalledPackage> {
         eprintln!(
             "[NpmResolver::install_package] 시작: package_name={}, version={:?}",
             package_name, version
         );
 
-        // 패키지 버전 결정
-        let version = match version {
-            Some(v) => {
-                eprintln!("[NpmResolver::install_package] 버전 지정됨: {}", v);
-                v.to_string()
+        // 정확한 버전이면 레지스트리 조회 없이 캐시 먼저 확인 (게시된 해시를 모르므로
+        // 설치할 때 기록한 sha512 값이 남아 있는 캐시만 사용)
+        if let Some(exact) = version.and_then(Version::parse) {
+            let installed = self.installed(package_name, &exact.to_string());
+            if installed.is_cached(&Integrity::default()) {
+                return Ok(installed);
             }
-            None => {
-                eprintln!("[NpmResolver::install_package] 최신 버전 조회 중...");
-                let latest = self.get_latest_version(package_name).await?;
-                eprintln!("[NpmResolver::install_package] 최신 버전: {}", latest);
-                latest
+        }
+
+        // 패키지 버전 결정 (범위, dist-tag는 레지스트리 메타데이터 기준)
+        let metadata = self.fetch_metadata(package_name).await?;
+        let package_version = match metadata.resolve_version(package_name, version) {
+            Err(error) if self.offline => {
+                return Err(error.context(format!(
+                    "오프라인 모드: {}@{}와 일치하는 버전이 캐시에 없습니다",
+                    package_name,
+                    version.unwrap_or("latest")
+                )));
             }
+            result => result?,
         };
+        let version = &package_version.version;
 
         // 캐시 경로
-        let package_dir = self.cache_dir.join(package_name).join(&version);
+        let installed = self.installed(package_name, version);
+        let package_dir = &installed.dir;
         eprintln!(
             "[NpmResolver::install_package] 캐시 경로: {:?}",
             package_dir
         );
 
         // 이미 설치되어 있으면 스킵
-        if package_dir.exists() {
-            eprintln!("[NpmResolver::install_package] 캐시 디렉토리 존재 확인 중...");
-            // package.json이 존재하는지 확인
-            let package_json_path = package_dir.join("package").join("package.json");
-            if package_json_path.exists() {
-                eprintln!(
-                    "[NpmResolver::install_package] 캐시된 패키지 사용: {:?}",
-                    package_dir
-                );
-                return Ok(package_dir);
-            }
-            eprintln!("[NpmResolver::install_package] package.json 없음, 재다운로드 필요");
+        let integrity = package_version.dist.integrity()?;
+        if installed.is_cached(&integrity) {
+            eprintln!(
+                "[NpmResolver::install_package] 캐시된 패키지 사용: {:?}",
+                package_dir
+            );
+            return Ok(installed);
+        }
+
+        // 같은 패키지를 설치 중인 다른 실행이 있으면 끝날 때까지 대기 후 다시 확인
+        let _lock = lock_package(package_dir, PACKAGE_LOCK_TIMEOUT).await?;
+        if installed.is_cached(&integrity) {
+            return Ok(installed);
+        }
+
+        if self.offline {
+            anyhow::bail!(
+                "오프라인 모드: {}@{} 패키지가 캐시에 없습니다",
+                package_name,
+                version
+            );
         }
 
-        // 패키지 메타데이터 가져오기
-        eprintln!("[NpmResolver::install_package] tarball URL 조회 중...");
-        let tarball_url = self.get_tarball_url(package_name, &version).await?;
+        let tarball_url = &package_version.dist.tarball;
         eprintln!(
             "[NpmResolver::install_package] tarball URL: {}",
             tarball_url
@@ -130,59 +547,187 @@ impl NpmResolver {
 
         // tarball 다운로드
         eprintln!("[NpmResolver::install_package] tarball 다운로드 시작...");
-        let tarball_data = self.download_tarball(&tarball_url).await?;
+        let tarball_data = self.download_tarball(tarball_url).await?;
         eprintln!(
             "[NpmResolver::install_package] tarball 다운로드 완료: {} bytes",
             tarball_data.len()
         );
 
-        // 기존 디렉토리 삭제 후 재생성
-        if package_dir.exists() {
-            fs::remove_dir_all(&package_dir)
-                .context("기존 패키지 디렉토리를 삭제할 수 없습니다")?;
-        }
-        fs::create_dir_all(&package_dir).context("패키지 디렉토리를 생성할 수 없습니다")?;
+        // 압축 해제 전에 레지스트리가 게시한 해시와 비교
+        let verified_integrity = integrity.verify(&tarball_data).with_context(|| {
+            format!("{}@{} tarball을 설치할 수 없습니다", package_name, version)
+        })?;
 
-        // 압축 해제
-        eprintln!("[NpmResolver::install_package] tarball 압축 해제 중...");
-        self.extract_tarball(&tarball_data, &package_dir)?;
+        // 임시 디렉토리에 압축을 풀고 완료 표시까지 기록한 뒤 캐시 경로로 한 번에 교체
+        // (중간에 종료되어도 캐시 경로에는 완료된 패키지만 남음)
+        remove_leftovers(package_dir);
+        let temp_dir = sibling_path(package_dir, "tmp");
+        eprintln!(
+            "[NpmResolver::install_package] tarball 압축 해제 중: {:?}",
+            temp_dir
+        );
+        let result = self
+            .extract_tarball(&tarball_data, &temp_dir)
+            .and_then(|()| {
+                fs::write(temp_dir.join(INTEGRITY_FILE), &verified_integrity)
+                    .context("무결성 값을 저장할 수 없습니다")?;
+                fs::write(temp_dir.join(COMPLETE_MARKER), "")
+                    .context("완료 표시를 저장할 수 없습니다")?;
+                replace_dir(&temp_dir, package_dir)
+            });
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&temp_dir);
+        }
+        result?;
         eprintln!("[NpmResolver::install_package] 압축 해제 완료");
 
         eprintln!(
             "[NpmResolver::install_package] 패키지 설치 완료: {:?}",
             package_dir
         );
-        Ok(package_dir)
+        Ok(installed)
     }
 
-    /// 최신 버전 가져오기
-    async fn get_latest_version(&self, package_name: &str) -> Result<String> {
-        let url = format!("{}/{}", self.registry_url, package_name);
-        let response = reqwest::get(&url).await?;
-        let metadata: NpmRegistryResponse = response.json().await?;
-        Ok(metadata.dist_tags.latest)
+    pub(crate) fn installed(&self, package_name: &str, version: &str) -> InstalledPackage {
+        InstalledPackage {
+            name: package_name.to_string(),
+            version: version.to_string(),
+            dir: self.cache_dir.join(package_name).join(version),
+        }
     }
 
-    /// tarball URL 가져오기
-    async fn get_tarball_url(&self, package_name: &str, version: &str) -> Result<String> {
-        let url = format!("{}/{}", self.registry_url, package_name);
-        let response = reqwest::get(&url).await?;
-        let metadata: serde_json::Value = response.json().await?;
+    /// 캐시에 설치된 패키지 안에서 import한 bare specifier를 `npm:이름@버전/하위경로`로 변환
+    ///
+    /// 가져오는 파일이 속한 패키지의 package.json에 선언된 범위로 `graph`에서 설치된
+    /// 버전을 찾습니다. 패키지 자기 자신을 가리키면 그 패키지의 버전을, 선언되지 않은
+    /// 패키지는 이미 설치된 버전을 사용합니다 (node_modules 호이스팅과 같은 동작).
+    /// 캐시 밖의 파일이거나 설치되지 않은 패키지면 `None`을 반환합니다.
+    pub fn resolve_package_import(
+        &self,
+        specifier: &str,
+        referrer_path: &Path,
+        graph: &DependencyGraph,
+    ) -> Option<String> {
+        let (name, _, subpath) = parse_package_specifier(specifier);
+        let referrer = self.package_of(referrer_path)?;
 
-        let version_data = metadata["versions"][version]
-            .as_object()
-            .context("패키지 버전을 찾을 수 없습니다")?;
+        let version = if name == referrer.name {
+            referrer.version
+        } else {
+            let range = read_dependencies(&referrer.dir)
+                .ok()?
+                .into_iter()
+                .find(|dependency| dependency.name == name)
+                .map(|dependency| dependency.range);
+            match range {
+                Some(range) => graph.find(&name, Some(&range)).unwrap_or(range),
+                // 선언하지 않고 호이스팅된 패키지에 기대는 경우 설치된 버전 사용
+                None => graph.find(&name, None)?,
+            }
+        };
 
-        let tarball_url = version_data["dist"]["tarball"]
-            .as_str()
-            .context("tarball URL을 찾을 수 없습니다")?;
+        Some(match subpath {
+            Some(subpath) => format!("npm:{}@{}/{}", name, version, subpath),
+            None => format!("npm:{}@{}", name, version),
+        })
+    }
+
+    /// 캐시 안의 파일 경로가 속한 패키지 (`캐시/<이름>/<버전>/package/...`)
+    pub(crate) fn package_of(&self, path: &Path) -> Option<InstalledPackage> {
+        let relative = path.strip_prefix(&self.cache_dir).ok()?;
+        let mut segments = relative
+            .components()
+            .map_while(|component| match component {
+                Component::Normal(segment) => segment.to_str(),
+                _ => None,
+            });
+        let first = segments.next()?;
+        let name = if first.starts_with('@') {
+            format!("{}/{}", first, segments.next()?)
+        } else {
+            first.to_string()
+        };
+        let version = segments.next()?;
+        Some(self.installed(&name, version))
+    }
+
+    /// 레지스트리에서 패키지 메타데이터(전체 버전 목록, dist-tags) 가져오기
+    ///
+    /// 캐시 디렉토리의 packument 캐시를 거치므로 TTL 안에는 요청하지 않습니다.
+    pub(crate) async fn fetch_metadata(&self, package_name: &str) -> Result<NpmRegistryResponse> {
+        if self.offline {
+            return self.offline_metadata(package_name);
+        }
+        let registry_url = self.registry_url_for(package_name);
+        let request = self.get(&format!("{}/{}", registry_url, package_name));
+        let packument = PackumentCache::new(&self.cache_dir, registry_url, self.metadata_ttl)
+            .fetch(package_name, request)
+            .await?;
+        serde_json::from_value(packument).context("패키지 메타데이터를 파싱할 수 없습니다")
+    }
+
+    /// 오프라인 모드의 메타데이터 (캐시에 설치를 마친 버전만 후보)
+    ///
+    /// dist-tag는 저장해 둔 packument에서 설치된 버전을 가리키는 것만 사용하고, `latest`를
+    /// 알 수 없으면 설치된 가장 높은 정식 버전을 `latest`로 봅니다.
+    fn offline_metadata(&self, package_name: &str) -> Result<NpmRegistryResponse> {
+        let versions: HashMap<String, PackageVersion> =
+            fs::read_dir(self.cache_dir.join(package_name))
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|version| {
+                    Version::parse(version).is_some()
+                        && self
+                            .installed(package_name, version)
+                            .is_cached(&Integrity::default())
+                })
+                .map(|version| {
+                    let cached = PackageVersion::cached(&version);
+                    (version, cached)
+                })
+                .collect();
+        if versions.is_empty() {
+            anyhow::bail!("오프라인 모드: {} 패키지가 캐시에 없습니다", package_name);
+        }
 
-        Ok(tarball_url.to_string())
+        let mut dist_tags: HashMap<String, String> = PackumentCache::new(
+            &self.cache_dir,
+            self.registry_url_for(package_name),
+            self.metadata_ttl,
+        )
+        .cached(package_name)
+        .and_then(|packument| serde_json::from_value(packument["dist-tags"].clone()).ok())
+        .unwrap_or_default();
+        dist_tags.retain(|_, version| versions.contains_key(version));
+        if !dist_tags.contains_key("latest") {
+            let latest = VersionReq::parse("*")
+                .and_then(|any| any.max_satisfying(versions.keys().map(String::as_str)))
+                .map(str::to_string);
+            if let Some(latest) = latest {
+                dist_tags.insert("latest".to_string(), latest);
+            }
+        }
+
+        Ok(NpmRegistryResponse {
+            dist_tags,
+            versions,
+        })
+    }
+
+    /// URL에 맞는 `.npmrc` 인증 정보를 붙인 GET 요청
+    fn get(&self, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.get(url);
+        match self.npmrc.authorization(url) {
+            Some(authorization) => request.header(AUTHORIZATION, authorization),
+            None => request,
+        }
     }
 
     /// tarball 다운로드
     async fn download_tarball(&self, url: &str) -> Result<Vec<u8>> {
-        let response = reqwest::get(url).await?;
+        let response = self.get(url).send().await?.error_for_status()?;
         let bytes = response.bytes().await?;
         Ok(bytes.to_vec())
     }
@@ -204,77 +749,25 @@ impl NpmResolver {
         Ok(())
     }
 
-    /// 패키지의 진입점 파일 찾기 (ESM 우선: exports.import > module > main)
+    /// 패키지의 진입점 파일 찾기 (`exports` > `module` > `main` > `index.js`)
     pub fn find_entry_point(&self, package_dir: &Path) -> Result<PathBuf> {
-        let package_json_path = package_dir.join("package").join("package.json");
-        eprintln!(
-            "[NpmResolver::find_entry_point] package.json 경로: {:?}",
-            package_json_path
-        );
-
-        let package_json_content =
-            fs::read_to_string(&package_json_path).context("package.json을 읽을 수 없습니다")?;
-        eprintln!("[NpmResolver::find_entry_point] package.json 읽기 완료");
-
-        let package_json: serde_json::Value = serde_json::from_str(&package_json_content)?;
-        eprintln!("[NpmResolver::find_entry_point] package.json 파싱 완료");
-
-        // ESM 우선순위로 찾기: exports.import > module > exports.default > main
-        let entry_point = package_json["exports"]
-            .as_object()
-            .and_then(|e| e.get("."))
-            .and_then(|e| {
-                e.as_object().and_then(|e| {
-                    // exports["."].import.default 우선 확인 (ESM)
-                    e.get("import").and_then(|i| {
-                        i.as_object()
-                            .and_then(|i| i.get("default"))
-                            .and_then(|d| d.as_str())
-                            .or_else(|| {
-                                // exports["."].import가 직접 문자열인 경우
-                                i.as_str()
-                            })
-                    })
-                })
-            })
-            .or_else(|| package_json["module"].as_str()) // module 필드 (ESM)
-            .or_else(|| {
-                // exports["."]가 직접 문자열인 경우
-                package_json["exports"]
-                    .as_object()
-                    .and_then(|e| e.get("."))
-                    .and_then(|e| e.as_str())
-            })
-            .or_else(|| {
-                // exports["."].require (CommonJS, 폴백)
-                package_json["exports"]
-                    .as_object()
-                    .and_then(|e| e.get("."))
-                    .and_then(|e| {
-                        e.as_object().and_then(|e| e.get("require")).and_then(|r| {
-                            r.as_object()
-                                .and_then(|r| r.get("default"))
-                                .and_then(|d| d.as_str())
-                                .or_else(|| r.as_str())
-                        })
-                    })
-            })
-            .or_else(|| package_json["main"].as_str()) // main (CommonJS, 최종 폴백)
-            .unwrap_or("index.js"); // 기본값
-
-        eprintln!(
-            "[NpmResolver::find_entry_point] 진입점 이름: {}",
-            entry_point
-        );
+        node_resolver::resolve_package(&package_dir.join("package"), None, &self.conditions)
+    }
 
-        let full_path = package_dir.join("package").join(entry_point);
-        eprintln!("[NpmResolver::find_entry_point] 전체 경로: {:?}", full_path);
+    /// 패키지 하위 경로의 파일 찾기 (예: `react/jsx-runtime`의 `jsx-runtime`)
+    ///
+    /// package.json에 `exports`가 있으면 선언된 경로만, 없으면 확장자와 index.js를 붙여 찾습니다.
+    pub fn find_subpath_entry(&self, package_dir: &Path, subpath: &str) -> Result<PathBuf> {
+        let entry_point = node_resolver::resolve_package(
+            &package_dir.join("package"),
+            Some(subpath),
+            &self.conditions,
+        )?;
         eprintln!(
-            "[NpmResolver::find_entry_point] 파일 존재 여부: {}",
-            full_path.exists()
+            "[NpmResolver::find_subpath_entry] 하위 경로 {}: {:?}",
+            subpath, entry_point
         );
-
-        Ok(full_path)
+        Ok(entry_point)
     }
 
     /// 패키지의 타입 정의 파일 찾기 (package.json의 types 또는 typings 필드)
@@ -340,3 +833,205 @@ impl NpmResolver {
         }
     }
 }
+
+/// 패키지 버전마다 하나씩 잡는 파일 잠금 (`캐시/<이름>/<버전>.lock`, 파일을 닫으면 해제)
+///
+/// 다른 프로세스가 잠금을 가지고 있으면 런타임 스레드를 막지 않도록 잠깐씩 쉬면서 다시 시도하고,
+/// `timeout`이 지나도 얻지 못하면 잠금 파일 경로를 담은 오류를 반환합니다.
+pub(crate) async fn lock_package(package_dir: &Path, timeout: Duration) -> Result<fs::File> {
+    let lock_path = sibling_lock_path(package_dir);
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent).context("패키지 디렉토리를 생성할 수 없습니다")?;
+    }
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("패키지 잠금 파일을 열 수 없습니다: {:?}", lock_path))?;
+    let started = Instant::now();
+    loop {
+        // Rust 1.89의 `File::try_lock`과 이름이 같으므로 fs4의 메서드를 명시해서 호출
+        match fs4::FileExt::try_lock(&file) {
+            Ok(()) => return Ok(file),
+            Err(fs4::TryLockError::WouldBlock) if started.elapsed() < timeout => {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            Err(fs4::TryLockError::WouldBlock) => {
+                anyhow::bail!(
+                    "{:?} 동안 패키지 잠금을 얻지 못했습니다 (다른 실행이 설치 중이거나 멈춘 상태): {:?}",
+                    timeout,
+                    lock_path
+                );
+            }
+            Err(fs4::TryLockError::Error(error)) => {
+                return Err(error)
+                    .with_context(|| format!("패키지 잠금을 얻을 수 없습니다: {:?}", lock_path));
+            }
+        }
+    }
+}
+
+fn sibling_lock_path(package_dir: &Path) -> PathBuf {
+    let mut name = package_dir.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    package_dir.with_file_name(name)
+}
+
+/// 캐시 디렉토리 옆의 숨김 임시 경로 (`캐시/<이름>/.<버전>.<종류>-<pid>-<시각>`)
+fn sibling_path(package_dir: &Path, kind: &str) -> PathBuf {
+    let version = package_dir
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    package_dir.with_file_name(format!(
+        ".{}.{}-{}-{}",
+        version,
+        kind,
+        std::process::id(),
+        nanos
+    ))
+}
+
+/// 이전에 중단된 설치가 남긴 임시 디렉토리 삭제 (잠금을 가진 상태에서만 호출)
+fn remove_leftovers(package_dir: &Path) {
+    let (Some(parent), Some(version)) = (package_dir.parent(), package_dir.file_name()) else {
+        return;
+    };
+    let prefix = format!(".{}.", version.to_string_lossy());
+    let Ok(entries) = fs::read_dir(parent) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with(&prefix) {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// 완성된 임시 디렉토리를 캐시 경로로 교체
+///
+/// 기존 디렉토리(불완전하거나 무결성 값이 다른 캐시)는 먼저 다른 이름으로 옮긴 뒤 삭제하므로
+/// 캐시 경로에는 항상 완료된 패키지만 있거나 아무것도 없습니다.
+fn replace_dir(temp_dir: &Path, package_dir: &Path) -> Result<()> {
+    if package_dir.exists() {
+        let old_dir = sibling_path(package_dir, "old");
+        fs::rename(package_dir, &old_dir).context("기존 패키지 디렉토리를 옮길 수 없습니다")?;
+        let _ = fs::remove_dir_all(&old_dir);
+    }
+    fs::rename(temp_dir, package_dir).context("패키지 디렉토리를 캐시로 옮길 수 없습니다")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempCache;
+
+    #[test]
+    fn test_resolve_package_version() {
+        let version = |version: &str| {
+            serde_json::json!({
+                "version": version,
+                "dist": { "tarball": format!("https://registry.example/pkg-{}.tgz", version) }
+            })
+        };
+        let metadata: NpmRegistryResponse = serde_json::from_value(serde_json::json!({
+            "dist-tags": { "latest": "2.1.0", "next": "3.0.0-beta.1" },
+            "versions": {
+                "1.0.0": version("1.0.0"),
+                "1.4.2": version("1.4.2"),
+                "2.1.0": version("2.1.0"),
+                "2.2.0": version("2.2.0"),
+                "3.0.0-beta.1": version("3.0.0-beta.1"),
+            }
+        }))
+        .unwrap();
+        let resolve = |requested: Option<&str>| {
+            metadata
+                .resolve_version("pkg", requested)
+                .map(|version| version.version.clone())
+                .ok()
+        };
+
+        assert_eq!(resolve(None).as_deref(), Some("2.1.0"));
+        assert_eq!(resolve(Some("next")).as_deref(), Some("3.0.0-beta.1"));
+        assert_eq!(resolve(Some("1.0.0")).as_deref(), Some("1.0.0"));
+        assert_eq!(resolve(Some("^1")).as_deref(), Some("1.4.2"));
+        // latest가 범위를 만족하면 더 높은 버전보다 latest 우선
+        assert_eq!(resolve(Some("^2")).as_deref(), Some("2.1.0"));
+        assert_eq!(resolve(Some(">=2.2")).as_deref(), Some("2.2.0"));
+        assert_eq!(resolve(Some("^4")), None);
+        assert_eq!(resolve(Some("beta")), None);
+    }
+
+    #[test]
+    fn test_parse_package_specifier() {
+        assert_eq!(
+            parse_package_specifier("react"),
+            ("react".to_string(), None, None)
+        );
+        assert_eq!(
+            parse_package_specifier("react@18.2.0/jsx-runtime"),
+            (
+                "react".to_string(),
+                Some("18.2.0".to_string()),
+                Some("jsx-runtime".to_string())
+            )
+        );
+        assert_eq!(
+            parse_package_specifier("@scope/pkg@^1/sub/path"),
+            (
+                "@scope/pkg".to_string(),
+                Some("^1".to_string()),
+                Some("sub/path".to_string())
+            )
+        );
+        assert_eq!(
+            parse_package_specifier("react-dom/server"),
+            ("react-dom".to_string(), None, Some("server".to_string()))
+        );
+        assert_eq!(
+            parse_package_specifier("date-fns/addDays"),
+            ("date-fns".to_string(), None, Some("addDays".to_string()))
+        );
+        assert_eq!(
+            parse_package_specifier("@scope/pkg/utils"),
+            ("@scope/pkg".to_string(), None, Some("utils".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_npmrc_registry_selection() {
+        let npmrc = NpmrcConfig::parse(
+            "registry=https://registry.example.com/\n\
+             @ourcompany:registry=https://npm.company.com/api/npm/\n",
+        );
+        let cache = TempCache::new();
+        let resolver =
+            NpmResolver::with_cache_dir(cache.path().to_path_buf(), DEFAULT_REGISTRY.to_string())
+                .unwrap()
+                .with_npmrc(npmrc)
+                .unwrap();
+        assert_eq!(resolver.registry_url(), "https://registry.example.com");
+        assert_eq!(
+            resolver.registry_url_for("@ourcompany/ui"),
+            "https://npm.company.com/api/npm"
+        );
+        assert_eq!(
+            resolver.registry_url_for("@other/ui"),
+            "https://registry.example.com"
+        );
+        assert_eq!(
+            resolver.registry_url_for("lodash"),
+            "https://registry.example.com"
+        );
+
+        // 읽을 수 없는 cafile은 오류
+        let missing_ca = NpmrcConfig::parse("cafile=/nonexistent/executejs-ca.pem");
+        assert!(resolver.clone().with_npmrc(missing_ca).is_err());
+    }
+}
@@ -0,0 +1,444 @@
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `.npmrc`에 레지스트리가 없을 때 사용하는 공개 레지스트리
+pub const DEFAULT_REGISTRY: &str = "https://registry.npmjs.org";
+
+/// `.npmrc` 형식의 npm 설정 (레지스트리, 스코프별 레지스트리, 인증, TLS)
+///
+/// 지원하는 항목:
+/// - `registry=URL`, `@scope:registry=URL`
+/// - `//host/path/:_authToken=TOKEN`, `//host/path/:_auth=BASE64`,
+///   `//host/path/:username=NAME`과 `//host/path/:_password=BASE64`
+/// - `strict-ssl=false`, `cafile=PATH`
+///
+/// 값의 `${ENV}`는 환경 변수로 바꿉니다. 정의되지 않은 환경 변수를 쓴 항목은 적용하지
+/// 않습니다.
+#[derive(Debug, Clone)]
+pub struct NpmrcConfig {
+    registry: Option<String>,
+    /// `@scope` → 레지스트리 URL
+    scopes: HashMap<String, String>,
+    /// `//host/path/` → 인증 정보
+    credentials: HashMap<String, Credentials>,
+    strict_ssl: bool,
+    ca_file: Option<PathBuf>,
+}
+
+impl Default for NpmrcConfig {
+    fn default() -> Self {
+        Self {
+            registry: None,
+            scopes: HashMap::new(),
+            credentials: HashMap::new(),
+            strict_ssl: true,
+            ca_file: None,
+        }
+    }
+}
+
+/// 레지스트리 하나의 인증 정보
+#[derive(Clone, Default)]
+struct Credentials {
+    token: Option<String>,
+    /// `base64(username:password)`
+    auth: Option<String>,
+    username: Option<String>,
+    /// base64로 인코딩된 비밀번호
+    password: Option<String>,
+}
+
+impl Credentials {
+    /// `Authorization` 헤더 값 (토큰 > `_auth` > `username`/`_password` 순서)
+    fn header(&self) -> Option<String> {
+        if let Some(token) = &self.token {
+            return Some(format!("Bearer {}", token));
+        }
+        if let Some(auth) = &self.auth {
+            return Some(format!("Basic {}", auth));
+        }
+        let password = String::from_utf8(STANDARD.decode(self.password.as_ref()?).ok()?).ok()?;
+        let basic = format!("{}:{}", self.username.as_ref()?, password);
+        Some(format!("Basic {}", STANDARD.encode(basic)))
+    }
+}
+
+/// 로그에 토큰과 비밀번호가 남지 않도록 값은 가림
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hidden = |value: &Option<String>| value.as_ref().map(|_| "***");
+        f.debug_struct("Credentials")
+            .field("token", &hidden(&self.token))
+            .field("auth", &hidden(&self.auth))
+            .field("username", &self.username)
+            .field("password", &hidden(&self.password))
+            .finish()
+    }
+}
+
+impl NpmrcConfig {
+    /// npm과 같은 순서로 설정 파일을 읽어서 합침 (없는 파일은 건너뜀)
+    ///
+    /// 전역(`NPM_CONFIG_GLOBALCONFIG` 또는 `{prefix}/etc/npmrc`), 사용자
+    /// (`NPM_CONFIG_USERCONFIG` 또는 `~/.npmrc`), 프로젝트(현재 디렉토리에서 가장 가까운
+    /// package.json이 있는 디렉토리의 `.npmrc`) 순서로 적용하므로 같은 항목은 프로젝트
+    /// 설정이 우선합니다.
+    pub fn load() -> Result<Self> {
+        let env = |name: &str| std::env::var(name).ok();
+        let project = std::env::current_dir()
+            .ok()
+            .map(|cwd| project_root(&cwd).join(".npmrc"));
+        let paths = [global_config_path(), user_config_path(), project];
+        Self::load_files(paths.iter().flatten(), &env)
+    }
+
+    /// 설정 파일을 차례로 적용 (뒤의 파일이 우선, 없는 파일은 건너뜀)
+    fn load_files<'a>(
+        paths: impl IntoIterator<Item = &'a PathBuf>,
+        env: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let mut config = Self::default();
+        for path in paths {
+            if path.is_file() {
+                config.apply(&read_config(path)?, env);
+            }
+        }
+        Ok(config)
+    }
+
+    /// 설정 파일 읽기
+    pub fn from_file(path: &Path) -> Result<Self> {
+        Ok(Self::parse(&read_config(path)?))
+    }
+
+    /// `.npmrc` 내용 파싱 (알 수 없는 항목과 형식이 잘못된 줄은 무시)
+    pub fn parse(content: &str) -> Self {
+        Self::parse_with(content, |name| std::env::var(name).ok())
+    }
+
+    /// `${NAME}`을 `env`로 찾아서 바꾸며 파싱
+    pub fn parse_with(content: &str, env: impl Fn(&str) -> Option<String>) -> Self {
+        let mut config = Self::default();
+        config.apply(content, &env);
+        config
+    }
+
+    /// 설정 내용을 현재 설정 위에 적용 (같은 항목은 나중에 적용한 값이 우선)
+    fn apply(&mut self, content: &str, env: &dyn Fn(&str) -> Option<String>) {
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim();
+            let value = match substitute_env(unquote(value.trim()), env) {
+                Ok(value) => value,
+                Err(name) => {
+                    // `${NAME}`을 그대로 토큰이나 URL로 보내지 않도록 항목을 건너뜀
+                    eprintln!(
+                        "[NpmrcConfig::apply] 환경 변수 {}가 정의되지 않아 {} 항목을 무시합니다",
+                        name, key
+                    );
+                    continue;
+                }
+            };
+
+            if key.starts_with("//") {
+                // `//host/path/:_authToken` (호스트에 포트가 있을 수 있으므로 마지막 `:`로 분리)
+                let Some((prefix, field)) = key.rsplit_once(':') else {
+                    continue;
+                };
+                let prefix = if prefix.ends_with('/') {
+                    prefix.to_string()
+                } else {
+                    format!("{}/", prefix)
+                };
+                let credentials = self.credentials.entry(prefix).or_default();
+                match field {
+                    "_authToken" => credentials.token = Some(value),
+                    "_auth" => credentials.auth = Some(value),
+                    "username" => credentials.username = Some(value),
+                    "_password" => credentials.password = Some(value),
+                    _ => {}
+                }
+            } else if let Some(scope) = key.strip_suffix(":registry") {
+                if scope.starts_with('@') {
+                    self.scopes.insert(scope.to_string(), trim_registry(&value));
+                }
+            } else {
+                match key {
+                    "registry" => self.registry = Some(trim_registry(&value)),
+                    "strict-ssl" => self.strict_ssl = value != "false",
+                    "cafile" => self.ca_file = Some(PathBuf::from(value)),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// 기본 레지스트리 (`registry=`, 설정하지 않았으면 `None`)
+    pub fn registry(&self) -> Option<&str> {
+        self.registry.as_deref()
+    }
+
+    /// 스코프(`@scope`)에 지정한 레지스트리
+    pub fn scope_registry(&self, scope: &str) -> Option<&str> {
+        self.scopes.get(scope).map(String::as_str)
+    }
+
+    /// 요청 URL에 보낼 `Authorization` 헤더 값
+    ///
+    /// npm과 같이 `//host/path/` 중 URL과 가장 길게 일치하는 항목의 인증 정보를 사용하므로,
+    /// 같은 레지스트리의 tarball에도 적용되고 다른 호스트에는 보내지 않습니다.
+    pub(crate) fn authorization(&self, url: &str) -> Option<String> {
+        let (_, without_scheme) = url.split_once(':')?;
+        self.credentials
+            .iter()
+            .filter(|(prefix, _)| without_scheme.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .and_then(|(_, credentials)| credentials.header())
+    }
+
+    /// `strict-ssl`과 `cafile`을 적용한 HTTP 클라이언트
+    pub(crate) fn http_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder();
+        if !self.strict_ssl {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+        if let Some(ca_file) = &self.ca_file {
+            let pem = fs::read(ca_file)
+                .with_context(|| format!("cafile을 읽을 수 없습니다: {:?}", ca_file))?;
+            let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+                .with_context(|| format!("cafile의 인증서를 파싱할 수 없습니다: {:?}", ca_file))?;
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        builder
+            .build()
+            .context("HTTP 클라이언트를 생성할 수 없습니다")
+    }
+}
+
+/// URL 뒤의 `/` 제거 (`{레지스트리}/{패키지}` 형식으로 요청)
+fn trim_registry(url: &str) -> String {
+    url.trim_end_matches('/').to_string()
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// `${NAME}`을 `env`에서 찾은 값으로 바꿈 (없는 변수가 있으면 그 이름을 반환)
+fn substitute_env<'a>(
+    value: &'a str,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, &'a str> {
+    let mut result = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + end];
+        result.push_str(&rest[..start]);
+        result.push_str(&env(name).ok_or(name)?);
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+fn read_config(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!(".npmrc를 읽을 수 없습니다: {:?}", path))
+}
+
+fn env_path(names: [&str; 2]) -> Option<PathBuf> {
+    names
+        .iter()
+        .find_map(std::env::var_os)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// 사용자 설정 파일 (`NPM_CONFIG_USERCONFIG` 또는 `~/.npmrc`)
+fn user_config_path() -> Option<PathBuf> {
+    env_path(["NPM_CONFIG_USERCONFIG", "npm_config_userconfig"])
+        .or_else(|| dirs::home_dir().map(|home| home.join(".npmrc")))
+}
+
+/// 전역 설정 파일 (`NPM_CONFIG_GLOBALCONFIG` 또는 `{prefix}/etc/npmrc`)
+///
+/// prefix는 `NPM_CONFIG_PREFIX`, 없으면 npm처럼 PATH에 있는 `node`의 설치 위치
+/// (Unix는 `bin`의 상위 디렉토리, Windows는 `node.exe`가 있는 디렉토리)입니다.
+fn global_config_path() -> Option<PathBuf> {
+    if let Some(path) = env_path(["NPM_CONFIG_GLOBALCONFIG", "npm_config_globalconfig"]) {
+        return Some(path);
+    }
+    let prefix = env_path(["NPM_CONFIG_PREFIX", "npm_config_prefix"]).or_else(|| {
+        let node = if cfg!(windows) { "node.exe" } else { "node" };
+        let bin_dir = std::env::split_paths(&std::env::var_os("PATH")?)
+            .find(|dir| dir.join(node).is_file())?;
+        if cfg!(windows) {
+            Some(bin_dir)
+        } else {
+            bin_dir.parent().map(Path::to_path_buf)
+        }
+    })?;
+    Some(prefix.join("etc").join("npmrc"))
+}
+
+/// npm의 프로젝트 디렉토리 (package.json이나 node_modules가 있는 가장 가까운 상위 디렉토리,
+/// 없으면 `cwd`)
+fn project_root(cwd: &Path) -> PathBuf {
+    cwd.ancestors()
+        .find(|dir| dir.join("package.json").is_file() || dir.join("node_modules").is_dir())
+        .unwrap_or(cwd)
+        .to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempCache;
+
+    #[test]
+    fn test_npmrc_registries_and_auth() {
+        let env = |name: &str| (name == "NPM_TOKEN").then(|| "secret-token".to_string());
+        let npmrc = NpmrcConfig::parse_with(
+            r#"
+            # 회사 레지스트리
+            registry=https://registry.example.com/
+            @ourcompany:registry=https://npm.company.com/api/npm/
+            //npm.company.com/api/npm/:_authToken=${NPM_TOKEN}
+            ; 로컬 레지스트리 (비밀번호는 base64)
+            //localhost:4873/:username=user
+            //localhost:4873/:_password="cGFzcw=="
+            strict-ssl=false
+            "#,
+            env,
+        );
+        assert_eq!(npmrc.registry(), Some("https://registry.example.com"));
+        assert_eq!(
+            npmrc.scope_registry("@ourcompany"),
+            Some("https://npm.company.com/api/npm")
+        );
+
+        let bearer = Some("Bearer secret-token".to_string());
+        assert_eq!(
+            npmrc.authorization("https://npm.company.com/api/npm/@ourcompany/ui"),
+            bearer
+        );
+        // 같은 레지스트리의 tarball에도 인증 정보를 붙임
+        assert_eq!(
+            npmrc.authorization("https://npm.company.com/api/npm/@ourcompany/ui/-/ui-1.0.0.tgz"),
+            bearer
+        );
+        assert_eq!(
+            npmrc.authorization("http://localhost:4873/pkg").as_deref(),
+            Some("Basic dXNlcjpwYXNz")
+        );
+        // 다른 호스트와 경로에는 보내지 않음
+        assert_eq!(
+            npmrc.authorization("https://npm.company.com/other/pkg"),
+            None
+        );
+        assert_eq!(
+            npmrc.authorization("https://npm.company.com.evil/api/npm/pkg"),
+            None
+        );
+        assert_eq!(
+            npmrc.authorization("https://registry.example.com/lodash"),
+            None
+        );
+        assert!(!format!("{:?}", npmrc).contains("secret-token"));
+    }
+
+    #[test]
+    fn test_npmrc_files_merge_in_npm_order() {
+        let dir = TempCache::new();
+        let global = dir.file(
+            "global",
+            "registry=https://global.example.com/\n\
+             @a:registry=https://global-a.example.com/\n\
+             //global.example.com/:_authToken=global-token\n\
+             strict-ssl=false\n",
+        );
+        let user = dir.file(
+            "user",
+            "registry=https://user.example.com/\n@b:registry=https://user-b.example.com/\n",
+        );
+        let project = dir.file(
+            "project",
+            "@a:registry=https://project-a.example.com/\n\
+             //project-a.example.com/:_authToken=${PROJECT_TOKEN}\n",
+        );
+        let missing = dir.join("missing");
+        let env = |name: &str| (name == "PROJECT_TOKEN").then(|| "project-token".to_string());
+
+        let config = NpmrcConfig::load_files([&global, &missing, &user, &project], &env).unwrap();
+        assert_eq!(config.registry(), Some("https://user.example.com"));
+        assert_eq!(
+            config.scope_registry("@a"),
+            Some("https://project-a.example.com")
+        );
+        assert_eq!(
+            config.scope_registry("@b"),
+            Some("https://user-b.example.com")
+        );
+        assert!(!config.strict_ssl);
+        assert_eq!(
+            config.authorization("https://project-a.example.com/@a/pkg"),
+            Some("Bearer project-token".to_string())
+        );
+        assert_eq!(
+            config.authorization("https://global.example.com/pkg"),
+            Some("Bearer global-token".to_string())
+        );
+    }
+
+    #[test]
+    fn test_project_root() {
+        let dir = TempCache::new();
+        let project = dir.join("app");
+        let nested = project.join("src/components");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(project_root(&nested), nested);
+
+        fs::write(project.join("package.json"), "{}").unwrap();
+        assert_eq!(project_root(&nested), project);
+        assert_eq!(project_root(&project), project);
+    }
+
+    #[test]
+    fn test_substitute_env() {
+        let env = |name: &str| (name == "TOKEN").then(|| "secret".to_string());
+        assert_eq!(substitute_env("a-${TOKEN}-b", &env).unwrap(), "a-secret-b");
+        assert_eq!(substitute_env("${TOKEN}-${MISSING}", &env), Err("MISSING"));
+        assert_eq!(substitute_env("${TOKEN", &env).unwrap(), "${TOKEN");
+
+        // 정의되지 않은 변수를 쓴 인증 정보는 보내지 않고, 설정도 이전 값을 유지
+        let npmrc = NpmrcConfig::parse_with(
+            "registry=https://registry.example.com/\n\
+             registry=https://${MISSING}/\n\
+             //registry.example.com/:_authToken=${MISSING}\n",
+            env,
+        );
+        assert_eq!(npmrc.registry(), Some("https://registry.example.com"));
+        assert_eq!(
+            npmrc.authorization("https://registry.example.com/lodash"),
+            None
+        );
+    }
+}
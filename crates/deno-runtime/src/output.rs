@@ -0,0 +1,282 @@
+use crate::{format_args, JsValuePreview, StyledSpan, StyledText};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+/// 콘솔 출력 레벨
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Log,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    /// `console.trace()` 호출
+    Trace,
+    /// `alert()` 호출
+    Alert,
+}
+
+impl LogLevel {
+    /// bootstrap.js에서 넘긴 레벨 이름 해석 (알 수 없는 값은 Log)
+    pub(crate) fn from_name(name: &str) -> Self {
+        match name {
+            "debug" => LogLevel::Debug,
+            "info" => LogLevel::Info,
+            "warn" => LogLevel::Warn,
+            "error" => LogLevel::Error,
+            "trace" => LogLevel::Trace,
+            "alert" => LogLevel::Alert,
+            _ => LogLevel::Log,
+        }
+    }
+
+    /// 텍스트 출력 시 붙이는 접두사
+    fn prefix(self) -> &'static str {
+        match self {
+            LogLevel::Log | LogLevel::Debug | LogLevel::Trace => "",
+            LogLevel::Info => "[INFO] ",
+            LogLevel::Warn => "[WARN] ",
+            LogLevel::Error => "[ERROR] ",
+            LogLevel::Alert => "[ALERT] ",
+        }
+    }
+}
+
+/// console 메서드가 호출된 소스 위치 (1부터 시작)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// `console.trace()`로 기록한 호출 스택의 한 프레임
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StackFrame {
+    #[serde(rename = "functionName")]
+    pub function_name: Option<String>,
+    pub location: SourceLocation,
+}
+
+impl fmt::Display for StackFrame {
+    /// V8 스택 트레이스 형식 (`at 함수 (파일:줄:열)`)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let SourceLocation { file, line, column } = &self.location;
+        match &self.function_name {
+            Some(name) => write!(f, "at {} ({}:{}:{})", name, file, line, column),
+            None => write!(f, "at {}:{}:{}", file, line, column),
+        }
+    }
+}
+
+/// `console.table()` 데이터
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsoleTable {
+    /// 첫 열 제목 (`(index)` 또는 Map/Set의 `(iteration index)`)
+    #[serde(rename = "indexHeader")]
+    pub index_header: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<ConsoleTableRow>,
+}
+
+/// 표의 한 행 (`cells`는 `columns`와 같은 순서, 값이 없는 칸은 None)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsoleTableRow {
+    pub index: String,
+    pub cells: Vec<Option<JsValuePreview>>,
+}
+
+/// 콘솔 출력의 종류
+///
+/// UI가 표나 접을 수 있는 그룹을 그릴 수 있도록 일반 메시지와 구분합니다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ConsoleEntryKind {
+    Message,
+    /// `console.group()` / `console.groupCollapsed()` 시작 (`message`는 그룹 제목)
+    GroupStart {
+        collapsed: bool,
+    },
+    /// `console.groupEnd()`
+    GroupEnd,
+    Table {
+        table: ConsoleTable,
+    },
+    Trace {
+        stack: Vec<StackFrame>,
+    },
+    /// `console.clear()`
+    Clear,
+}
+
+/// 콘솔 출력 한 건
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsoleEntry {
+    pub level: LogLevel,
+    /// 호출 시각 (Unix epoch 기준 밀리초)
+    pub timestamp: u64,
+    pub location: Option<SourceLocation>,
+    /// 인자별 구조화된 값
+    pub args: Vec<JsValuePreview>,
+    /// 인자를 이어 붙인 표시용 문자열 (Node의 `util.format`과 같은 형식)
+    pub message: String,
+    /// 스타일이 적용된 표시용 조각 (`message`를 나눈 것)
+    pub spans: Vec<StyledSpan>,
+    /// `console.group()` 중첩 깊이
+    #[serde(rename = "groupDepth")]
+    pub group_depth: usize,
+    #[serde(flatten)]
+    pub kind: ConsoleEntryKind,
+}
+
+impl ConsoleEntry {
+    pub(crate) fn new(
+        level: LogLevel,
+        location: Option<SourceLocation>,
+        args: Vec<JsValuePreview>,
+    ) -> Self {
+        let formatted = format_args(&args);
+
+        Self {
+            level,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+            location,
+            args,
+            message: formatted.text(),
+            spans: formatted.into_spans(),
+            group_depth: 0,
+            kind: ConsoleEntryKind::Message,
+        }
+    }
+
+    pub(crate) fn with_kind(mut self, kind: ConsoleEntryKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// 인자를 이어 붙인 기본 표시 대신 별도로 만든 표시 사용 (console.dir, console.table 등)
+    pub(crate) fn with_message(mut self, message: StyledText) -> Self {
+        self.message = message.text();
+        self.spans = message.into_spans();
+        self
+    }
+
+    /// 문자열 하나로 된 출력 (alert, print)
+    pub(crate) fn text(level: LogLevel, message: String) -> Self {
+        Self::new(level, None, vec![JsValuePreview::String { value: message }])
+    }
+}
+
+/// 콘솔 출력을 발생 즉시 전달받는 콜백
+///
+/// 이벤트 루프가 끝날 때까지 기다리지 않고 `console.*` 호출마다 바로 호출되므로
+/// `setInterval`로 진행 상황을 찍는 스크립트도 실시간으로 보여줄 수 있습니다.
+/// 실행 스레드에서 호출되므로 오래 걸리는 작업은 채널로 넘겨서 처리해야 합니다.
+#[derive(Clone)]
+pub struct OutputSink(Arc<dyn Fn(ConsoleEntry) + Send + Sync>);
+
+impl OutputSink {
+    pub fn new(callback: impl Fn(ConsoleEntry) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    /// 출력을 채널로 받는 sink 생성
+    pub fn channel() -> (Self, mpsc::UnboundedReceiver<ConsoleEntry>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let sink = Self::new(move |entry| {
+            // 받는 쪽이 먼저 닫혀도 실행은 계속
+            let _ = sender.send(entry);
+        });
+        (sink, receiver)
+    }
+
+    pub(crate) fn send(&self, entry: ConsoleEntry) {
+        (self.0)(entry)
+    }
+}
+
+impl fmt::Debug for OutputSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OutputSink")
+    }
+}
+
+/// 실행 하나의 콘솔 출력을 모으는 수집기
+///
+/// JsRuntime마다 OpState에 하나씩 보관되므로 여러 실행기가 동시에 돌아도
+/// 출력이 서로 섞이지 않습니다.
+#[derive(Default)]
+pub(crate) struct OutputCollector {
+    output: ExecutionOutput,
+    sink: Option<OutputSink>,
+}
+
+impl OutputCollector {
+    /// 이전 출력을 비우고 이번 실행의 sink 지정
+    pub(crate) fn begin(&mut self, sink: Option<OutputSink>) {
+        self.output = ExecutionOutput::new();
+        self.sink = sink;
+    }
+
+    /// 출력 추가 (sink가 있으면 바로 전달)
+    pub(crate) fn push(&mut self, entry: ConsoleEntry) {
+        if let Some(sink) = &self.sink {
+            sink.send(entry.clone());
+        }
+        self.output.push(entry);
+    }
+
+    /// 모은 출력을 꺼내고 sink 해제
+    pub(crate) fn finish(&mut self) -> ExecutionOutput {
+        self.sink = None;
+        std::mem::take(&mut self.output)
+    }
+}
+
+/// JavaScript 실행 결과를 저장하는 구조체
+///
+/// 콘솔 출력은 레벨과 관계없이 호출된 순서대로 보관됩니다.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecutionOutput {
+    pub entries: Vec<ConsoleEntry>,
+}
+
+impl ExecutionOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, entry: ConsoleEntry) {
+        self.entries.push(entry);
+    }
+
+    /// 호출 순서대로 한 줄씩 이어 붙인 텍스트 (레벨 접두사 포함)
+    ///
+    /// 그룹 안의 출력은 Node처럼 깊이마다 두 칸씩 들여씁니다.
+    pub fn to_text(&self) -> String {
+        self.entries
+            .iter()
+            .filter(|entry| entry.kind == ConsoleEntryKind::Message || !entry.message.is_empty())
+            .map(|entry| {
+                let indentation = "  ".repeat(entry.group_depth);
+                let text = format!("{}{}", entry.level.prefix(), entry.message);
+                text.split('\n')
+                    .map(|line| format!("{}{}", indentation, line))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
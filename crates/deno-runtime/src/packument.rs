@@ -0,0 +1,179 @@
+use anyhow::{Context, Result};
+use reqwest::header::{ACCEPT, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 레지스트리 메타데이터(packument)를 저장하는 디렉토리 (`캐시/.packuments/`)
+///
+/// npm 패키지 이름은 `.`으로 시작할 수 없으므로 패키지 디렉토리와 겹치지 않습니다.
+const PACKUMENT_DIR: &str = ".packuments";
+
+/// 설치에 필요한 필드만 담은 축약 형식을 우선 요청 (지원하지 않는 레지스트리는 전체 JSON)
+const ACCEPT_PACKUMENT: &str =
+    "application/vnd.npm.install-v1+json; q=1.0, application/json; q=0.8, */*";
+
+/// 저장한 메타데이터를 다시 확인하지 않고 사용하는 기본 시간
+pub const DEFAULT_METADATA_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// 디스크에 저장한 packument와 재검증에 쓸 응답 헤더
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedPackument {
+    /// 받아 온 레지스트리 (레지스트리가 바뀌면 사용하지 않음)
+    registry_url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// 마지막으로 받거나 재검증한 시각 (UNIX 밀리초)
+    fetched_at_ms: u64,
+    packument: serde_json::Value,
+}
+
+/// `NpmResolver` 캐시 디렉토리의 packument 캐시
+///
+/// `ttl` 안에는 저장한 값을 그대로 쓰고, 지나면 `ETag`/`Last-Modified`로 조건부 요청을
+/// 보내 `304 Not Modified`면 저장한 값을 계속 사용합니다.
+pub(crate) struct PackumentCache<'a> {
+    cache_dir: &'a Path,
+    registry_url: &'a str,
+    ttl: Duration,
+}
+
+impl<'a> PackumentCache<'a> {
+    pub(crate) fn new(cache_dir: &'a Path, registry_url: &'a str, ttl: Duration) -> Self {
+        Self {
+            cache_dir,
+            registry_url,
+            ttl,
+        }
+    }
+
+    /// 패키지의 packument 가져오기 (캐시, 조건부 요청, 전체 요청 순서)
+    ///
+    /// `request`는 인증 정보를 붙인 `{레지스트리}/{패키지}` 요청입니다.
+    pub(crate) async fn fetch(
+        &self,
+        package_name: &str,
+        request: reqwest::RequestBuilder,
+    ) -> Result<serde_json::Value> {
+        let path = self.path(package_name);
+        let cached = self.read(&path);
+
+        if let Some(cached) = &cached {
+            let age = Duration::from_millis(now_ms().saturating_sub(cached.fetched_at_ms));
+            if age < self.ttl {
+                return Ok(cached.packument.clone());
+            }
+        }
+
+        let mut request = request.header(ACCEPT, ACCEPT_PACKUMENT);
+        if let Some(cached) = &cached {
+            if let Some(etag) = &cached.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(error) => {
+                // 레지스트리에 접근할 수 없으면 오래된 값이라도 사용
+                let Some(cached) = cached else {
+                    return Err(error.into());
+                };
+                eprintln!(
+                    "[PackumentCache::fetch] 레지스트리 요청 실패, 오래된 메타데이터 사용: {}",
+                    error
+                );
+                return Ok(cached.packument);
+            }
+        };
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            // 저장한 값이 없으면 조건부 헤더를 보내지 않았으므로 레지스트리(프록시)의 잘못된 응답
+            let Some(mut cached) = cached else {
+                anyhow::bail!(
+                    "레지스트리가 조건부 요청이 아닌 {} 메타데이터 요청에 304 Not Modified로 응답했습니다",
+                    package_name
+                );
+            };
+            cached.fetched_at_ms = now_ms();
+            self.write(&path, &cached);
+            return Ok(cached.packument);
+        }
+
+        let response = response.error_for_status()?;
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let packument: serde_json::Value = response
+            .json()
+            .await
+            .context("패키지 메타데이터를 파싱할 수 없습니다")?;
+
+        let cached = CachedPackument {
+            registry_url: self.registry_url.to_string(),
+            etag,
+            last_modified,
+            fetched_at_ms: now_ms(),
+            packument,
+        };
+        self.write(&path, &cached);
+        Ok(cached.packument)
+    }
+
+    /// 요청 없이 저장해 둔 packument만 읽기 (TTL과 관계없음, 오프라인 모드에서 사용)
+    pub(crate) fn cached(&self, package_name: &str) -> Option<serde_json::Value> {
+        self.read(&self.path(package_name))
+            .map(|cached| cached.packument)
+    }
+
+    /// `@scope/pkg`는 `@scope%2fpkg.json`으로 저장
+    fn path(&self, package_name: &str) -> PathBuf {
+        self.cache_dir
+            .join(PACKUMENT_DIR)
+            .join(format!("{}.json", package_name.replace('/', "%2f")))
+    }
+
+    fn read(&self, path: &Path) -> Option<CachedPackument> {
+        let content = fs::read_to_string(path).ok()?;
+        let cached: CachedPackument = serde_json::from_str(&content).ok()?;
+        (cached.registry_url == self.registry_url).then_some(cached)
+    }
+
+    /// 임시 파일에 쓰고 옮겨서 동시에 읽는 쪽이 일부만 쓰인 파일을 보지 않게 함
+    ///
+    /// 캐시는 없어도 동작하므로 저장에 실패하면 로그만 남깁니다.
+    fn write(&self, path: &Path, cached: &CachedPackument) {
+        let result = (|| -> Result<()> {
+            fs::create_dir_all(self.cache_dir.join(PACKUMENT_DIR))?;
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.subsec_nanos())
+                .unwrap_or_default();
+            let temp_path = path.with_extension(format!("json.{}-{}", std::process::id(), nanos));
+            fs::write(&temp_path, serde_json::to_vec(cached)?)?;
+            fs::rename(&temp_path, path)?;
+            Ok(())
+        })();
+        if let Err(error) = result {
+            eprintln!("[PackumentCache::write] 메타데이터 저장 실패: {}", error);
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
@@ -0,0 +1,487 @@
+//! npm(node-semver) 방식의 버전과 버전 범위
+//!
+//! `^1.2.3`, `~1.2`, `1.x`, `>=1.0.0 <2.0.0`, `1.0.0 - 2.0.0`, `^1 || ^2` 같은 범위를 해석하고,
+//! 레지스트리의 버전 목록에서 범위를 만족하는 가장 높은 버전을 고릅니다.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// prerelease 식별자 (숫자 식별자는 문자열 식별자보다 낮음)
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Identifier {
+    Numeric(u64),
+    Alpha(String),
+}
+
+impl Identifier {
+    fn parse(part: &str) -> Option<Self> {
+        if part.is_empty() {
+            return None;
+        }
+        if part.bytes().all(|b| b.is_ascii_digit()) {
+            part.parse().ok().map(Identifier::Numeric)
+        } else if part.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-') {
+            Some(Identifier::Alpha(part.to_string()))
+        } else {
+            None
+        }
+    }
+}
+
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Identifier::Numeric(a), Identifier::Numeric(b)) => a.cmp(b),
+            (Identifier::Numeric(_), Identifier::Alpha(_)) => Ordering::Less,
+            (Identifier::Alpha(_), Identifier::Numeric(_)) => Ordering::Greater,
+            (Identifier::Alpha(a), Identifier::Alpha(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Identifier::Numeric(n) => write!(f, "{}", n),
+            Identifier::Alpha(s) => f.write_str(s),
+        }
+    }
+}
+
+/// `major.minor.patch[-prerelease][+build]` 형식의 버전 (build 메타데이터는 비교에서 무시)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    prerelease: Vec<Identifier>,
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            prerelease: Vec::new(),
+        }
+    }
+
+    /// 정확한 버전 문자열 파싱 (앞의 `v`, `=`는 허용)
+    pub fn parse(text: &str) -> Option<Self> {
+        let partial = Partial::parse(text.trim())?;
+        match (partial.major, partial.minor, partial.patch) {
+            (Some(major), Some(minor), Some(patch)) => Some(Self {
+                major,
+                minor,
+                patch,
+                prerelease: partial.prerelease,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.prerelease.is_empty()
+    }
+
+    /// prerelease를 제외한 (major, minor, patch)가 같은지 확인
+    fn same_release(&self, other: &Version) -> bool {
+        (self.major, self.minor, self.patch) == (other.major, other.minor, other.patch)
+    }
+
+    /// 이 버전의 가장 낮은 prerelease (`<2.0.0-0`처럼 상한에서 prerelease까지 제외할 때 사용)
+    fn lowest_prerelease(mut self) -> Self {
+        self.prerelease = vec![Identifier::Numeric(0)];
+        self
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| {
+                // prerelease가 없는 버전이 더 높음
+                match (self.prerelease.is_empty(), other.prerelease.is_empty()) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                    (false, false) => self.prerelease.cmp(&other.prerelease),
+                }
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        for (index, identifier) in self.prerelease.iter().enumerate() {
+            f.write_str(if index == 0 { "-" } else { "." })?;
+            write!(f, "{}", identifier)?;
+        }
+        Ok(())
+    }
+}
+
+/// 일부가 생략되었거나 `x`/`*`인 버전 (`1`, `1.2`, `1.x`, `*`)
+#[derive(Debug, Clone)]
+struct Partial {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    prerelease: Vec<Identifier>,
+}
+
+impl Partial {
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim_start_matches('=').trim_start_matches('v');
+        // build 메타데이터 제거
+        let text = text.split_once('+').map_or(text, |(version, _)| version);
+        if text.is_empty() {
+            return Some(Self::any());
+        }
+
+        let (core, prerelease) = match text.split_once('-') {
+            Some((core, prerelease)) => (core, Some(prerelease)),
+            None => (text, None),
+        };
+        let mut parts = core.split('.');
+        let mut next = || -> Option<Option<u64>> {
+            match parts.next() {
+                None | Some("x" | "X" | "*") => Some(None),
+                Some(part) if part.bytes().all(|b| b.is_ascii_digit()) => {
+                    part.parse().ok().map(Some)
+                }
+                Some(_) => None,
+            }
+        };
+        let major = next()?;
+        let minor = next()?;
+        let patch = next()?;
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let prerelease = match prerelease {
+            // prerelease는 완전한 버전에만 붙을 수 있음
+            Some(prerelease) if patch.is_some() && minor.is_some() && major.is_some() => prerelease
+                .split('.')
+                .map(Identifier::parse)
+                .collect::<Option<Vec<_>>>()?,
+            Some(_) => return None,
+            None => Vec::new(),
+        };
+
+        // x 뒤에 숫자가 오는 경우(`1.x.3`)는 뒤쪽을 무시
+        let minor = major.and(minor);
+        let patch = minor.and(patch);
+        Some(Self {
+            major,
+            minor,
+            patch,
+            prerelease,
+        })
+    }
+
+    fn any() -> Self {
+        Self {
+            major: None,
+            minor: None,
+            patch: None,
+            prerelease: Vec::new(),
+        }
+    }
+
+    /// 생략된 부분을 0으로 채운 하한
+    fn floor(&self) -> Version {
+        Version {
+            major: self.major.unwrap_or(0),
+            minor: self.minor.unwrap_or(0),
+            patch: self.patch.unwrap_or(0),
+            prerelease: self.prerelease.clone(),
+        }
+    }
+
+    /// 생략된 부분 바로 위의 버전 (`1.2` → `1.3.0`, `1` → `2.0.0`, 완전한 버전이면 None)
+    fn next_up(&self) -> Option<Version> {
+        match (self.major, self.minor, self.patch) {
+            (Some(major), None, _) => Some(Version::new(major + 1, 0, 0)),
+            (Some(major), Some(minor), None) => Some(Version::new(major, minor + 1, 0)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+}
+
+#[derive(Debug, Clone)]
+struct Comparator {
+    op: Op,
+    version: Version,
+}
+
+impl Comparator {
+    fn new(op: Op, version: Version) -> Self {
+        Self { op, version }
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        let ordering = version.cmp(&self.version);
+        match self.op {
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+            Op::Eq => ordering == Ordering::Equal,
+        }
+    }
+}
+
+/// 모든 비교식을 만족해야 하는 집합 (비어 있으면 모든 정식 버전 허용)
+#[derive(Debug, Clone)]
+struct ComparatorSet {
+    comparators: Vec<Comparator>,
+    /// `>1 <1` 같이 아무 버전도 만족할 수 없는 집합
+    never: bool,
+}
+
+impl ComparatorSet {
+    fn parse(text: &str) -> Option<Self> {
+        let mut set = Self {
+            comparators: Vec::new(),
+            never: false,
+        };
+
+        // 하이픈 범위 (`1.2.3 - 2.3`)
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        if let [from, "-", to] = tokens[..] {
+            let from = Partial::parse(from)?;
+            let to = Partial::parse(to)?;
+            if from.major.is_some() {
+                set.push(Op::Ge, from.floor());
+            }
+            match to.next_up() {
+                Some(upper) => set.push(Op::Lt, upper.lowest_prerelease()),
+                None if to.major.is_some() => set.push(Op::Le, to.floor()),
+                None => {}
+            }
+            return Some(set);
+        }
+
+        // 연산자와 버전 사이의 공백 허용 (`>= 1.2.3`)
+        let mut merged = Vec::new();
+        let mut pending_op = String::new();
+        for token in tokens {
+            if token
+                .bytes()
+                .all(|b| matches!(b, b'<' | b'>' | b'=' | b'~' | b'^'))
+            {
+                pending_op.push_str(token);
+            } else {
+                merged.push(format!("{}{}", pending_op, token));
+                pending_op.clear();
+            }
+        }
+        if !pending_op.is_empty() {
+            return None;
+        }
+
+        for token in &merged {
+            set.add_token(token)?;
+        }
+        Some(set)
+    }
+
+    fn push(&mut self, op: Op, version: Version) {
+        self.comparators.push(Comparator::new(op, version));
+    }
+
+    fn add_token(&mut self, token: &str) -> Option<()> {
+        let (op, rest) = ["~>", ">=", "<=", ">", "<", "=", "~", "^"]
+            .iter()
+            .find_map(|op| token.strip_prefix(op).map(|rest| (*op, rest)))
+            .unwrap_or(("", token));
+        let partial = Partial::parse(rest)?;
+
+        match op {
+            "^" => self.add_caret(&partial),
+            "~" | "~>" => self.add_tilde(&partial),
+            "" | "=" => match partial.next_up() {
+                // `1.2` → >=1.2.0 <1.3.0-0
+                Some(upper) => {
+                    self.push(Op::Ge, partial.floor());
+                    self.push(Op::Lt, upper.lowest_prerelease());
+                }
+                None if partial.major.is_some() => self.push(Op::Eq, partial.floor()),
+                None => {}
+            },
+            ">" => match (partial.major.is_some(), partial.next_up()) {
+                (false, _) => self.never = true,
+                // `>1.2` → >=1.3.0
+                (true, Some(upper)) => self.push(Op::Ge, upper),
+                (true, None) => self.push(Op::Gt, partial.floor()),
+            },
+            ">=" => {
+                if partial.major.is_some() {
+                    self.push(Op::Ge, partial.floor());
+                }
+            }
+            "<" => {
+                if partial.major.is_none() {
+                    self.never = true;
+                } else if partial.patch.is_none() {
+                    // `<1.2` → <1.2.0-0
+                    self.push(Op::Lt, partial.floor().lowest_prerelease());
+                } else {
+                    self.push(Op::Lt, partial.floor());
+                }
+            }
+            "<=" => match (partial.major.is_some(), partial.next_up()) {
+                (false, _) => {}
+                // `<=1.2` → <1.3.0-0
+                (true, Some(upper)) => self.push(Op::Lt, upper.lowest_prerelease()),
+                (true, None) => self.push(Op::Le, partial.floor()),
+            },
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// `^`: 가장 왼쪽의 0이 아닌 자리는 바꾸지 않는 범위
+    fn add_caret(&mut self, partial: &Partial) {
+        let Some(major) = partial.major else {
+            return;
+        };
+        let upper = match (major, partial.minor, partial.patch) {
+            (0, Some(0), Some(patch)) => Version::new(0, 0, patch + 1),
+            (0, Some(minor), _) if minor > 0 || partial.patch.is_none() => {
+                Version::new(0, minor + 1, 0)
+            }
+            (0, None, _) => Version::new(1, 0, 0),
+            _ => Version::new(major + 1, 0, 0),
+        };
+        self.push(Op::Ge, partial.floor());
+        self.push(Op::Lt, upper.lowest_prerelease());
+    }
+
+    /// `~`: minor가 있으면 patch만, 없으면 minor까지 바뀌는 범위
+    fn add_tilde(&mut self, partial: &Partial) {
+        let Some(major) = partial.major else {
+            return;
+        };
+        let upper = match partial.minor {
+            Some(minor) => Version::new(major, minor + 1, 0),
+            None => Version::new(major + 1, 0, 0),
+        };
+        self.push(Op::Ge, partial.floor());
+        self.push(Op::Lt, upper.lowest_prerelease());
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        if self.never || !self.comparators.iter().all(|c| c.matches(version)) {
+            return false;
+        }
+        // prerelease 버전은 같은 major.minor.patch의 prerelease를 명시한 경우에만 허용
+        // (`>=1.2.3-beta.1`은 `1.2.3-beta.2`는 허용하지만 `1.2.4-beta.1`은 허용하지 않음)
+        !version.is_prerelease()
+            || self
+                .comparators
+                .iter()
+                .any(|c| c.version.is_prerelease() && c.version.same_release(version))
+    }
+}
+
+/// `||`로 묶인 버전 범위
+#[derive(Debug, Clone)]
+pub(crate) struct VersionReq {
+    sets: Vec<ComparatorSet>,
+}
+
+impl VersionReq {
+    /// 범위 파싱 (빈 문자열, `*`, `latest`가 아닌 태그 이름 등은 여기서 처리하지 않음)
+    pub fn parse(text: &str) -> Option<Self> {
+        let sets = text
+            .split("||")
+            .map(|set| ComparatorSet::parse(set.trim()))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { sets })
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        self.sets.iter().any(|set| set.matches(version))
+    }
+
+    /// 후보 중에서 범위를 만족하는 가장 높은 버전
+    pub fn max_satisfying<'a>(
+        &self,
+        versions: impl IntoIterator<Item = &'a str>,
+    ) -> Option<&'a str> {
+        versions
+            .into_iter()
+            .filter_map(|text| Version::parse(text).map(|version| (version, text)))
+            .filter(|(version, _)| self.matches(version))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, text)| text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semver_ranges() {
+        let matches = |range: &str, version: &str| {
+            VersionReq::parse(range)
+                .unwrap()
+                .matches(&Version::parse(version).unwrap())
+        };
+        assert!(matches("^1.2.3", "1.9.0"));
+        assert!(!matches("^1.2.3", "2.0.0"));
+        assert!(matches("^0.2.3", "0.2.9"));
+        assert!(!matches("^0.2.3", "0.3.0"));
+        assert!(!matches("^0.0.3", "0.0.4"));
+        assert!(matches("~1.2", "1.2.7"));
+        assert!(!matches("~1.2.3", "1.3.0"));
+        assert!(matches("1.x", "1.4.0"));
+        assert!(matches("18", "18.2.0"));
+        assert!(matches(">= 1.0.0 <2", "1.5.0"));
+        assert!(matches("1.0.0 - 2.0", "2.0.9"));
+        assert!(!matches("1.0.0 - 2.0", "2.1.0"));
+        assert!(matches("^1 || ^3", "3.1.0"));
+        assert!(!matches("^1 || ^3", "2.0.0"));
+
+        // prerelease는 같은 버전의 prerelease를 범위에 명시한 경우에만 허용
+        assert!(!matches("*", "1.0.0-beta.1"));
+        assert!(!matches("^1.0.0", "1.5.0-beta.1"));
+        assert!(matches("^1.2.3-beta.2", "1.2.3-beta.10"));
+        assert!(!matches("^1.2.3-beta.2", "1.2.4-beta.1"));
+        assert!(VersionReq::parse("not a range").is_none());
+
+        let range = VersionReq::parse("^4").unwrap();
+        assert_eq!(
+            range.max_satisfying(["4.17.20", "4.17.21", "5.0.0", "4.18.0-rc.1"]),
+            Some("4.17.21")
+        );
+    }
+}
@@ -0,0 +1,452 @@
+use crate::cjs::install_required_packages;
+use crate::interrupt::Watchdog;
+use crate::transpile::{needs_transpile, transpile};
+use crate::{
+    begin_output, create_runtime, finish_output, into_script_error, preview_global,
+    resolve_completion_value, source_maps, CancelHandle, ExecutionError, ExecutionResult,
+    ExecutorOptions, JsValuePreview, JsxRuntime, Language, OutputSink, PartialOutput, ScriptError,
+    SourceLocation, USER_CODE_MODULE_URL,
+};
+use anyhow::Result;
+use deno_core::error::JsError;
+use deno_core::{
+    v8, InspectorSessionKind, InspectorSessionOptions, JsRuntime, LocalInspectorSession,
+    ModuleSpecifier, PollEventLoopOptions,
+};
+use futures::FutureExt;
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::{mpsc, oneshot};
+
+/// REPL 세션 식별자
+pub type SessionId = u64;
+
+/// 세션 ID 발급용 카운터
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 마지막 평가 결과를 보관하는 전역 심볼 키
+const LAST_RESULT_KEY: &str = "Symbol.for('executejs.lastResult')";
+
+/// 마지막으로 잡히지 않은 예외를 보관하는 전역 심볼 키
+const LAST_ERROR_KEY: &str = "Symbol.for('executejs.lastError')";
+
+/// 세션 스레드로 보내는 명령
+enum SessionCommand {
+    Execute {
+        code: String,
+        /// 이번 실행에만 쓸 출력 sink (None이면 세션 옵션의 sink)
+        sink: Option<OutputSink>,
+        reply: oneshot::Sender<Result<ExecutionResult>>,
+    },
+    Reset {
+        reply: oneshot::Sender<Result<()>>,
+    },
+}
+
+/// REPL 세션 핸들
+///
+/// 실제 JsRuntime은 `!Send`이므로 세션 전용 스레드가 소유하고,
+/// 핸들은 채널로 명령만 전달합니다. 마지막 핸들이 drop되면 스레드도 종료됩니다.
+#[derive(Clone)]
+pub struct ReplSession {
+    id: SessionId,
+    sender: mpsc::UnboundedSender<SessionCommand>,
+    cancel_handle: CancelHandle,
+}
+
+impl ReplSession {
+    /// 세션 스레드를 띄우고 JsRuntime 초기화가 끝날 때까지 대기
+    pub(crate) async fn spawn(options: ExecutorOptions) -> Result<Self> {
+        let id = NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let (ready_tx, ready_rx) = oneshot::channel();
+        let cancel_handle = CancelHandle::default();
+
+        let thread_cancel_handle = cancel_handle.clone();
+        std::thread::Builder::new()
+            .name(format!("executejs-session-{}", id))
+            .spawn(move || run_session_thread(options, thread_cancel_handle, receiver, ready_tx))
+            .map_err(|e| anyhow::anyhow!("세션 스레드 생성 실패: {}", e))?;
+
+        ready_rx
+            .await
+            .map_err(|_| anyhow::anyhow!("세션 스레드가 초기화 중에 종료되었습니다"))??;
+
+        Ok(Self {
+            id,
+            sender,
+            cancel_handle,
+        })
+    }
+
+    /// 세션 ID 반환
+    pub fn id(&self) -> SessionId {
+        self.id
+    }
+
+    /// 세션에서 실행 중인 코드를 취소하기 위한 핸들
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel_handle.clone()
+    }
+
+    /// 세션의 전역 스코프에서 코드 실행 후 콘솔 출력과 마지막 표현식의 값 반환
+    pub async fn execute(&self, code: &str) -> Result<ExecutionResult> {
+        self.execute_with_sink(code, None).await
+    }
+
+    /// 이번 실행의 콘솔 출력을 `sink`로 실시간 전달하면서 코드 실행
+    pub async fn execute_with_sink(
+        &self,
+        code: &str,
+        sink: Option<OutputSink>,
+    ) -> Result<ExecutionResult> {
+        let (reply, rx) = oneshot::channel();
+        self.send(SessionCommand::Execute {
+            code: code.to_string(),
+            sink,
+            reply,
+        })?;
+        rx.await
+            .map_err(|_| anyhow::anyhow!("세션이 종료되었습니다: {}", self.id))?
+    }
+
+    /// 세션의 JsRuntime을 새로 만들어 전역 상태 초기화
+    pub async fn reset(&self) -> Result<()> {
+        let (reply, rx) = oneshot::channel();
+        self.send(SessionCommand::Reset { reply })?;
+        rx.await
+            .map_err(|_| anyhow::anyhow!("세션이 종료되었습니다: {}", self.id))?
+    }
+
+    fn send(&self, command: SessionCommand) -> Result<()> {
+        self.sender
+            .send(command)
+            .map_err(|_| anyhow::anyhow!("세션이 종료되었습니다: {}", self.id))
+    }
+}
+
+/// 세션 스레드 본체: 전용 current-thread 런타임에서 명령을 순서대로 처리
+fn run_session_thread(
+    options: ExecutorOptions,
+    cancel_handle: CancelHandle,
+    mut receiver: mpsc::UnboundedReceiver<SessionCommand>,
+    ready: oneshot::Sender<Result<()>>,
+) {
+    let rt = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(rt) => rt,
+        Err(e) => {
+            let _ = ready.send(Err(anyhow::anyhow!("세션 런타임 생성 실패: {}", e)));
+            return;
+        }
+    };
+
+    rt.block_on(async move {
+        let mut repl = match ReplRuntime::new(&options, &cancel_handle) {
+            Ok(repl) => {
+                let _ = ready.send(Ok(()));
+                repl
+            }
+            Err(e) => {
+                let _ = ready.send(Err(e));
+                return;
+            }
+        };
+
+        while let Some(command) = receiver.recv().await {
+            match command {
+                SessionCommand::Execute { code, sink, reply } => {
+                    let result = repl
+                        .execute(
+                            &code,
+                            Language::JavaScript,
+                            sink.or_else(|| options.output_sink.clone()),
+                            &options,
+                            &cancel_handle,
+                        )
+                        .await;
+
+                    // 힙 한도에 도달한 런타임은 한도가 늘어난 상태이므로 새로 생성
+                    if let Err(error) = &result {
+                        if let Some(ExecutionError::OutOfMemory(_)) = error.downcast_ref() {
+                            match ReplRuntime::new(&options, &cancel_handle) {
+                                Ok(fresh) => repl = fresh,
+                                Err(e) => eprintln!("[ReplSession] 런타임 재생성 실패: {}", e),
+                            }
+                        }
+                    }
+                    let _ = reply.send(result);
+                }
+                SessionCommand::Reset { reply } => {
+                    let result =
+                        ReplRuntime::new(&options, &cancel_handle).map(|fresh| repl = fresh);
+                    let _ = reply.send(result);
+                }
+            }
+        }
+
+        eprintln!("[ReplSession] 세션 종료");
+    });
+}
+
+/// top-level await가 있는 스크립트를 새 런타임에서 REPL 모드로 한 번 평가
+///
+/// V8 REPL 모드가 top-level await를 지원하므로 코드를 감싸지 않아도 되고, 마지막 표현식의
+/// 값(`await` 결과 포함)도 일반 스크립트처럼 반환됩니다.
+pub(crate) async fn run_await_script(
+    code: &str,
+    language: Language,
+    options: &ExecutorOptions,
+    cancel_handle: &CancelHandle,
+) -> Result<ExecutionResult> {
+    let mut repl = ReplRuntime::new(options, cancel_handle)?;
+    repl.execute(
+        code,
+        language,
+        options.output_sink.clone(),
+        options,
+        cancel_handle,
+    )
+    .await
+}
+
+/// 인스펙터 세션을 통해 REPL 모드로 평가하는 JsRuntime
+///
+/// V8의 `replMode`를 사용하므로 Deno/Node REPL처럼 최상위 `let`/`const`를
+/// 다시 선언할 수 있고, 이전 스니펫의 바인딩이 다음 스니펫에서 그대로 보입니다.
+struct ReplRuntime {
+    js_runtime: JsRuntime,
+    session: LocalInspectorSession,
+    /// 평가한 스니펫 수 (스니펫마다 다른 스크립트 이름을 붙이는 데 사용)
+    snippet_count: u64,
+}
+
+impl ReplRuntime {
+    fn new(options: &ExecutorOptions, cancel_handle: &CancelHandle) -> Result<Self> {
+        let mut js_runtime = create_runtime(options, cancel_handle, true)?;
+        let session =
+            js_runtime
+                .inspector()
+                .borrow()
+                .create_local_session(InspectorSessionOptions {
+                    kind: InspectorSessionKind::NonBlocking {
+                        wait_for_disconnect: false,
+                    },
+                });
+        Ok(Self {
+            js_runtime,
+            session,
+            snippet_count: 0,
+        })
+    }
+
+    /// 출력 수집과 타임아웃/취소 감시를 붙여 코드 평가
+    async fn execute(
+        &mut self,
+        code: &str,
+        language: Language,
+        sink: Option<OutputSink>,
+        options: &ExecutorOptions,
+        cancel_handle: &CancelHandle,
+    ) -> Result<ExecutionResult> {
+        begin_output(&mut self.js_runtime, sink);
+
+        cancel_handle.begin(self.js_runtime.v8_isolate().thread_safe_handle());
+        let watchdog = Watchdog::start(cancel_handle, options.timeout);
+        let result = self
+            .evaluate(code, language, &options.jsx, cancel_handle)
+            .await;
+        drop(watchdog);
+        // 힙 한도로 런타임을 새로 만들기 전에 출력을 먼저 꺼냄
+        let output = finish_output(&mut self.js_runtime);
+
+        // 중단된 isolate는 다음 스니펫을 실행할 수 있도록 종료 상태 해제
+        match cancel_handle.resolve(result) {
+            Ok(value) => Ok(ExecutionResult { output, value }),
+            Err(error) => {
+                self.js_runtime.v8_isolate().cancel_terminate_execution();
+                Err(PartialOutput::attach(error, output))
+            }
+        }
+    }
+
+    /// 코드를 REPL 모드로 평가하고 남은 이벤트 루프(타이머, Promise)까지 실행
+    ///
+    /// 마지막 표현식의 값(Promise면 이행된 값)을 미리보기로 반환합니다.
+    /// TypeScript/JSX는 변환한 코드를 평가하고 소스 맵을 스니펫 이름으로 등록합니다.
+    async fn evaluate(
+        &mut self,
+        code: &str,
+        language: Language,
+        jsx: &JsxRuntime,
+        cancel_handle: &CancelHandle,
+    ) -> Result<Option<JsValuePreview>> {
+        // 예외 위치를 이번 스니펫 기준으로 찾을 수 있도록 스크립트 이름 지정
+        self.snippet_count += 1;
+        let script_name = format!("[executejs:repl:{}]", self.snippet_count);
+        let is_user_file = |file: &str| file == script_name || file == USER_CODE_MODULE_URL;
+
+        // top-level await가 있을 수 있으므로 모듈로 파싱해서 변환
+        let media_type = language.media_type();
+        let expression = if needs_transpile(media_type) {
+            let specifier = ModuleSpecifier::parse(USER_CODE_MODULE_URL)
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            let transpiled = transpile(&specifier, media_type, code.to_string(), true, jsx)
+                .map_err(|error| into_script_error(error, code, &is_user_file))?;
+            source_maps(&mut self.js_runtime).insert(&script_name, transpiled.source_map);
+            transpiled.code
+        } else {
+            code.to_string()
+        };
+
+        // require op 안에서는 패키지를 설치할 수 없으므로 require할 npm 패키지를 미리 설치
+        cancel_handle
+            .race(install_required_packages(&mut self.js_runtime, &expression))
+            .await?;
+
+        let response = cancel_handle
+            .race(self.post_message(
+                "Runtime.evaluate",
+                json!({
+                    "expression": format!("{}\n//# sourceURL={}", expression, script_name),
+                    "replMode": true,
+                }),
+            ))
+            .await?;
+
+        if let Some(details) = response.get("exceptionDetails") {
+            let error = self.exception_error(details, code, &script_name).await?;
+            return Err(ExecutionError::Exception(error).into());
+        }
+
+        let value = cancel_handle
+            .race(self.completion_value(&response["result"]))
+            .await
+            .map_err(|error| into_script_error(error, code, &is_user_file))?;
+
+        cancel_handle
+            .race(
+                self.js_runtime
+                    .run_event_loop(PollEventLoopOptions::default()),
+            )
+            .await
+            .map_err(|error| into_script_error(error, code, &is_user_file))?;
+        Ok(value)
+    }
+
+    /// `exceptionDetails`로 ScriptError 생성
+    ///
+    /// 던진 값이 객체면 V8 값으로 다시 읽어 `JsError`를 만들고, 호출 스택에 이번 스니펫이
+    /// 없으면(문법 오류 등) `exceptionDetails`의 위치를 사용합니다.
+    async fn exception_error(
+        &mut self,
+        details: &serde_json::Value,
+        code: &str,
+        script_name: &str,
+    ) -> Result<ScriptError> {
+        // 변환한 스니펫은 소스 맵의 원본 이름(사용자 코드 URL)으로 프레임이 바뀜
+        let is_user_file = |file: &str| file == script_name || file == USER_CODE_MODULE_URL;
+        let exception = &details["exception"];
+        let mut error = match exception["objectId"].as_str() {
+            Some(object_id) => {
+                let value = self.store_remote_object(object_id, LAST_ERROR_KEY).await?;
+                let scope = &mut self.js_runtime.handle_scope();
+                let local = v8::Local::new(scope, value);
+                let js_error = JsError::from_v8_exception(scope, local);
+                ScriptError::from_js_error(&js_error, code, &is_user_file)
+            }
+            None => {
+                // 원시값을 던진 경우
+                let message = match JsValuePreview::from_remote_primitive(exception) {
+                    Some(JsValuePreview::String { value }) => value,
+                    Some(preview) => preview.to_string(),
+                    None => details["text"].as_str().unwrap_or_default().to_string(),
+                };
+                ScriptError {
+                    name: None,
+                    message,
+                    frames: Vec::new(),
+                    location: None,
+                    source_line: None,
+                    cause: None,
+                }
+            }
+        };
+
+        let url = details["url"].as_str().unwrap_or_default();
+        if error.location.is_none() && (url.is_empty() || url == script_name) {
+            if let (Some(line), Some(column)) = (
+                details["lineNumber"].as_u64(),
+                details["columnNumber"].as_u64(),
+            ) {
+                let location = SourceLocation {
+                    file: script_name.to_string(),
+                    line: line as usize + 1,
+                    column: column as usize + 1,
+                };
+                error.set_location(location, code);
+            }
+        }
+        Ok(error)
+    }
+
+    /// `Runtime.evaluate` 결과(RemoteObject)를 미리보기로 변환
+    ///
+    /// 원시값은 RemoteObject에서 바로 만들고, 객체는 전역 심볼 키에 보관한 뒤
+    /// V8 값으로 다시 읽어 Rust 쪽에서 미리보기를 만듭니다.
+    async fn completion_value(
+        &mut self,
+        remote: &serde_json::Value,
+    ) -> Result<Option<JsValuePreview>> {
+        if let Some(preview) = JsValuePreview::from_remote_primitive(remote) {
+            return Ok(Some(preview));
+        }
+        let Some(object_id) = remote["objectId"].as_str() else {
+            return Ok(None);
+        };
+
+        let value = self.store_remote_object(object_id, LAST_RESULT_KEY).await?;
+        let value = resolve_completion_value(&mut self.js_runtime, value).await?;
+        Ok(Some(preview_global(&mut self.js_runtime, value)))
+    }
+
+    /// RemoteObject를 전역 심볼 키에 보관한 뒤 V8 값으로 다시 읽음
+    async fn store_remote_object(
+        &mut self,
+        object_id: &str,
+        key: &str,
+    ) -> Result<v8::Global<v8::Value>> {
+        self.post_message(
+            "Runtime.callFunctionOn",
+            json!({
+                "objectId": object_id,
+                "functionDeclaration": format!(
+                    "function () {{ 'use strict'; globalThis[{}] = this; }}",
+                    key
+                ),
+            }),
+        )
+        .await?;
+
+        self.js_runtime
+            .execute_script("[executejs:remote_object]", format!("globalThis[{}]", key))
+    }
+
+    /// 이벤트 루프를 돌리면서 인스펙터 메시지 응답 대기
+    async fn post_message(
+        &mut self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        self.js_runtime
+            .with_event_loop_future(
+                self.session
+                    .post_message(method, Some(params))
+                    .boxed_local(),
+                PollEventLoopOptions::default(),
+            )
+            .await
+    }
+}
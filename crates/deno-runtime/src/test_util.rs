@@ -0,0 +1,45 @@
+//! 테스트에서 함께 쓰는 임시 디렉토리 fixture
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 캐시 fixture에 기록하는 무결성 값 (설치할 때 저장하는 sha512 형식, 내용과는 무관)
+pub(crate) const FIXTURE_INTEGRITY: &str = "sha512-AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA==";
+
+/// 테스트마다 새로 만드는 임시 디렉토리 (패닉이 나도 drop될 때 삭제됨)
+pub(crate) struct TempCache {
+    dir: tempfile::TempDir,
+}
+
+impl TempCache {
+    pub(crate) fn new() -> Self {
+        Self {
+            dir: tempfile::tempdir().unwrap(),
+        }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub(crate) fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.dir.path().join(path)
+    }
+
+    /// 상위 디렉토리까지 만들어서 파일을 쓰고 전체 경로 반환
+    pub(crate) fn file(&self, path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> PathBuf {
+        let path = self.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// `이름/버전` 캐시 항목을 설치가 끝난 상태로 만들고 항목 디렉토리 반환
+    pub(crate) fn package(&self, name: &str, version: &str, manifest: &str) -> PathBuf {
+        let dir = self.join(name).join(version);
+        self.file(dir.join("package/package.json"), manifest);
+        self.file(dir.join("integrity"), FIXTURE_INTEGRITY);
+        self.file(dir.join(".complete"), "");
+        dir
+    }
+}
@@ -0,0 +1,189 @@
+use anyhow::Result;
+use deno_ast::{
+    EmitOptions, JsxAutomaticOptions, JsxClassicOptions, MediaType, ParseParams, SourceMapOption,
+    TranspileModuleOptions, TranspileOptions,
+};
+use deno_core::ModuleSpecifier;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// 사용자 코드의 언어
+///
+/// TypeScript는 실행 전에 타입만 제거해서 JavaScript로 바꿉니다 (타입 검사는 하지 않음).
+/// JSX/TSX는 `JsxRuntime` 설정에 따라 함수 호출로 변환합니다.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    JavaScript,
+    TypeScript,
+    Jsx,
+    Tsx,
+}
+
+impl Language {
+    /// 파일 확장자로 언어 결정 (`.ts`, `.mts`, `.cts`면 TypeScript, `.jsx`/`.tsx`는 각각 JSX/TSX)
+    pub fn from_filename(filename: &str) -> Self {
+        match MediaType::from_path(Path::new(filename)) {
+            MediaType::TypeScript | MediaType::Mts | MediaType::Cts => Language::TypeScript,
+            MediaType::Jsx => Language::Jsx,
+            MediaType::Tsx => Language::Tsx,
+            _ => Language::JavaScript,
+        }
+    }
+
+    pub(crate) fn media_type(self) -> MediaType {
+        match self {
+            Language::JavaScript => MediaType::JavaScript,
+            Language::TypeScript => MediaType::TypeScript,
+            Language::Jsx => MediaType::Jsx,
+            Language::Tsx => MediaType::Tsx,
+        }
+    }
+
+    /// JSX 구문을 포함할 수 있는 언어인지 확인
+    pub(crate) fn has_jsx(self) -> bool {
+        matches!(self, Language::Jsx | Language::Tsx)
+    }
+}
+
+/// JSX 변환 방식
+///
+/// - `Classic`: `<div />`를 `factory("div", null)`로 변환 (기본값 `React.createElement`)
+/// - `Automatic`: `import_source + "/jsx-runtime"`에서 `jsx` 함수를 가져와 호출
+///   (예: `npm:react`면 `npm:react/jsx-runtime`)
+///
+/// 코드 안의 `/** @jsx h */`, `/** @jsxImportSource preact */` 같은 pragma 주석이
+/// 이 설정보다 우선합니다.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "runtime", rename_all = "camelCase")]
+pub enum JsxRuntime {
+    #[serde(rename_all = "camelCase")]
+    Classic {
+        factory: String,
+        fragment_factory: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Automatic { import_source: String },
+}
+
+impl Default for JsxRuntime {
+    fn default() -> Self {
+        JsxRuntime::Classic {
+            factory: "React.createElement".to_string(),
+            fragment_factory: "React.Fragment".to_string(),
+        }
+    }
+}
+
+impl JsxRuntime {
+    /// 변환 결과에 `import`가 추가되는지 여부 (모듈로 실행해야 함)
+    pub(crate) fn is_automatic(&self) -> bool {
+        matches!(self, JsxRuntime::Automatic { .. })
+    }
+
+    fn transpile_options(&self) -> TranspileOptions {
+        let jsx = match self {
+            JsxRuntime::Classic {
+                factory,
+                fragment_factory,
+            } => deno_ast::JsxRuntime::Classic(JsxClassicOptions {
+                factory: factory.clone(),
+                fragment_factory: fragment_factory.clone(),
+            }),
+            JsxRuntime::Automatic { import_source } => {
+                deno_ast::JsxRuntime::Automatic(JsxAutomaticOptions {
+                    development: false,
+                    import_source: Some(import_source.clone()),
+                })
+            }
+        };
+        TranspileOptions {
+            jsx: Some(jsx),
+            ..Default::default()
+        }
+    }
+}
+
+/// 실행 전에 JavaScript로 변환해야 하는 형식인지 확인
+pub(crate) fn needs_transpile(media_type: MediaType) -> bool {
+    matches!(
+        media_type,
+        MediaType::TypeScript | MediaType::Mts | MediaType::Cts | MediaType::Jsx | MediaType::Tsx
+    )
+}
+
+/// 변환된 코드와 원래 코드로 되돌리는 소스 맵
+pub(crate) struct Transpiled {
+    pub code: String,
+    pub source_map: Option<Vec<u8>>,
+}
+
+/// 변환한 스크립트/모듈의 소스 맵 (V8에 등록된 파일 이름 기준)
+///
+/// `NpmModuleLoader::get_source_map`이 이 맵을 돌려주므로 `JsError`의 스택 프레임이
+/// 변환 전 코드의 줄/열을 가리킵니다.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SourceMaps(Arc<Mutex<HashMap<String, Vec<u8>>>>);
+
+impl SourceMaps {
+    /// 소스 맵 등록 (`None`이면 같은 이름으로 남아 있던 맵 제거)
+    pub fn insert(&self, file_name: &str, source_map: Option<Vec<u8>>) {
+        let mut maps = self.0.lock().unwrap();
+        match source_map {
+            Some(source_map) => maps.insert(file_name.to_string(), source_map),
+            None => maps.remove(file_name),
+        };
+    }
+
+    pub fn get(&self, file_name: &str) -> Option<Vec<u8>> {
+        self.0.lock().unwrap().get(file_name).cloned()
+    }
+}
+
+/// 소스를 JavaScript로 변환
+///
+/// `is_module`이 false면 스크립트로 파싱하므로 마지막 표현식(완료 값)이 그대로 남습니다.
+/// 문법 오류는 `ParseDiagnostic`으로 반환되어 `into_script_error`에서 위치가 담긴
+/// ScriptError로 바뀝니다. 변환 결과에는 원래 코드로 되돌리는 소스 맵이 함께 담깁니다.
+pub(crate) fn transpile(
+    specifier: &ModuleSpecifier,
+    media_type: MediaType,
+    code: String,
+    is_module: bool,
+    jsx: &JsxRuntime,
+) -> Result<Transpiled> {
+    let params = ParseParams {
+        specifier: specifier.clone(),
+        text: code.into(),
+        media_type,
+        capture_tokens: false,
+        scope_analysis: false,
+        maybe_syntax: None,
+    };
+    let parsed = if is_module {
+        deno_ast::parse_module(params)?
+    } else {
+        deno_ast::parse_script(params)?
+    };
+    // 복구 가능한 문법 오류도 실행하지 않고 첫 번째 오류를 보고
+    if let Some(diagnostic) = parsed.diagnostics().first() {
+        return Err(diagnostic.clone().into());
+    }
+
+    let transpiled = parsed.transpile(
+        &jsx.transpile_options(),
+        &TranspileModuleOptions::default(),
+        &EmitOptions {
+            source_map: SourceMapOption::Separate,
+            ..Default::default()
+        },
+    )?;
+    let source = transpiled.into_source();
+    Ok(Transpiled {
+        code: source.text,
+        source_map: source.source_map.map(String::into_bytes),
+    })
+}
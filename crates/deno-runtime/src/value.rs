@@ -0,0 +1,387 @@
+use crate::inspect::number_to_string;
+use deno_core::v8;
+use serde::{Deserialize, Serialize};
+
+/// 중첩 객체를 펼칠 최대 깊이 (Node `util.inspect`의 `depth`와 같은 의미)
+///
+/// 최상위 객체가 깊이 0이고, 깊이가 이 값보다 큰 객체(깊이 5)부터 `Truncated`로 담습니다.
+/// `console.log("%o")`가 쓰는 깊이 4까지 담아 두고, 기본 출력은
+/// inspect에서 2단계로 자릅니다.
+const MAX_DEPTH: usize = 4;
+
+/// 배열/객체/Map/Set에서 미리보기에 담을 최대 항목 수
+const MAX_ITEMS: usize = 100;
+
+/// JavaScript 값의 구조화된 미리보기
+///
+/// V8 값을 Rust 쪽에서 순회하여 만들며, 프론트엔드가 트리 형태로 그릴 수 있도록
+/// 직렬화됩니다. 깊이 제한을 넘거나 순환 참조를 만나면 더 펼치지 않습니다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum JsValuePreview {
+    Undefined,
+    Null,
+    Boolean {
+        value: bool,
+    },
+    /// NaN, Infinity, -0도 표현할 수 있도록 문자열로 보관
+    Number {
+        description: String,
+    },
+    String {
+        value: String,
+    },
+    BigInt {
+        description: String,
+    },
+    Symbol {
+        description: String,
+    },
+    Function {
+        name: String,
+        #[serde(rename = "isClass")]
+        is_class: bool,
+    },
+    Array {
+        items: Vec<JsValuePreview>,
+        length: usize,
+    },
+    Object {
+        #[serde(rename = "className")]
+        class_name: String,
+        properties: Vec<PropertyPreview>,
+        /// 미리보기에 담지 못한 나머지 속성 수
+        more: usize,
+    },
+    Map {
+        entries: Vec<MapEntryPreview>,
+        size: usize,
+    },
+    Set {
+        items: Vec<JsValuePreview>,
+        size: usize,
+    },
+    Date {
+        iso: String,
+    },
+    RegExp {
+        source: String,
+    },
+    Error {
+        name: String,
+        message: String,
+        stack: Option<String>,
+    },
+    Promise {
+        state: PromiseState,
+        value: Option<Box<JsValuePreview>>,
+    },
+    /// 순환 참조 (`depth`: 참조 대상 객체의 깊이, 최상위 객체가 0)
+    Circular {
+        depth: usize,
+    },
+    /// 깊이 제한으로 펼치지 않은 객체 (예: `[Object]`, `[Array]`)
+    Truncated {
+        #[serde(rename = "className")]
+        class_name: String,
+    },
+}
+
+/// 객체 속성 미리보기
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyPreview {
+    pub key: String,
+    pub value: JsValuePreview,
+}
+
+/// Map 항목 미리보기
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapEntryPreview {
+    pub key: JsValuePreview,
+    pub value: JsValuePreview,
+}
+
+/// Promise 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromiseState {
+    Pending,
+    Fulfilled,
+    Rejected,
+}
+
+impl JsValuePreview {
+    /// V8 값으로부터 미리보기 생성
+    pub fn from_v8<'s>(
+        scope: &mut v8::HandleScope<'s>,
+        value: v8::Local<'s, v8::Value>,
+    ) -> JsValuePreview {
+        let mut seen = Vec::new();
+        preview_value(scope, value, 0, &mut seen)
+    }
+
+    /// 인스펙터 프로토콜의 원시값 RemoteObject로부터 미리보기 생성
+    ///
+    /// `objectId`가 있는 객체는 V8 값을 직접 읽어야 하므로 None을 반환합니다.
+    pub(crate) fn from_remote_primitive(remote: &serde_json::Value) -> Option<JsValuePreview> {
+        if remote.get("objectId").is_some() {
+            return None;
+        }
+
+        let description = || {
+            remote["description"]
+                .as_str()
+                .or_else(|| remote["unserializableValue"].as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| remote["value"].to_string())
+        };
+
+        let preview = match remote["type"].as_str()? {
+            "undefined" => JsValuePreview::Undefined,
+            "boolean" => JsValuePreview::Boolean {
+                value: remote["value"].as_bool().unwrap_or_default(),
+            },
+            "number" => JsValuePreview::Number {
+                description: description(),
+            },
+            "string" => JsValuePreview::String {
+                value: remote["value"].as_str().unwrap_or_default().to_string(),
+            },
+            "bigint" => JsValuePreview::BigInt {
+                description: description(),
+            },
+            "object" if remote["subtype"].as_str() == Some("null") => JsValuePreview::Null,
+            _ => return None,
+        };
+        Some(preview)
+    }
+}
+
+fn preview_value<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    value: v8::Local<'s, v8::Value>,
+    depth: usize,
+    seen: &mut Vec<v8::Local<'s, v8::Object>>,
+) -> JsValuePreview {
+    if value.is_undefined() {
+        return JsValuePreview::Undefined;
+    }
+    if value.is_null() {
+        return JsValuePreview::Null;
+    }
+    if value.is_boolean() {
+        return JsValuePreview::Boolean {
+            value: value.is_true(),
+        };
+    }
+    if value.is_number() {
+        let number = value.number_value(scope).unwrap_or(f64::NAN);
+        return JsValuePreview::Number {
+            description: number_to_string(number),
+        };
+    }
+    if value.is_string() {
+        return JsValuePreview::String {
+            value: value.to_rust_string_lossy(scope),
+        };
+    }
+    if value.is_big_int() {
+        return JsValuePreview::BigInt {
+            description: format!("{}n", value.to_rust_string_lossy(scope)),
+        };
+    }
+    if let Ok(symbol) = v8::Local::<v8::Symbol>::try_from(value) {
+        let description = symbol.description(scope);
+        let description = if description.is_undefined() {
+            String::new()
+        } else {
+            description.to_rust_string_lossy(scope)
+        };
+        return JsValuePreview::Symbol {
+            description: format!("Symbol({})", description),
+        };
+    }
+    if let Ok(function) = v8::Local::<v8::Function>::try_from(value) {
+        let name = function.get_name(scope).to_rust_string_lossy(scope);
+        let is_class = function
+            .to_string(scope)
+            .map(|source| source.to_rust_string_lossy(scope).starts_with("class"))
+            .unwrap_or(false);
+        return JsValuePreview::Function { name, is_class };
+    }
+
+    let Some(object) = value.to_object(scope) else {
+        return JsValuePreview::String {
+            value: value.to_rust_string_lossy(scope),
+        };
+    };
+
+    if value.is_date() {
+        let iso =
+            call_method(scope, object, "toISOString").unwrap_or_else(|| "Invalid Date".to_string());
+        return JsValuePreview::Date { iso };
+    }
+    if value.is_reg_exp() {
+        return JsValuePreview::RegExp {
+            source: value.to_rust_string_lossy(scope),
+        };
+    }
+    if value.is_native_error() {
+        return JsValuePreview::Error {
+            name: get_string(scope, object, "name").unwrap_or_else(|| "Error".to_string()),
+            message: get_string(scope, object, "message").unwrap_or_default(),
+            stack: get_string(scope, object, "stack"),
+        };
+    }
+
+    if let Some(depth) = seen.iter().position(|parent| parent.strict_equals(value)) {
+        return JsValuePreview::Circular { depth };
+    }
+
+    let class_name = object.get_constructor_name().to_rust_string_lossy(scope);
+    if depth > MAX_DEPTH {
+        let class_name = if value.is_array() {
+            "Array".to_string()
+        } else {
+            class_name
+        };
+        return JsValuePreview::Truncated { class_name };
+    }
+
+    seen.push(object);
+    let preview = preview_object(scope, value, object, class_name, depth, seen);
+    seen.pop();
+    preview
+}
+
+fn preview_object<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    value: v8::Local<'s, v8::Value>,
+    object: v8::Local<'s, v8::Object>,
+    class_name: String,
+    depth: usize,
+    seen: &mut Vec<v8::Local<'s, v8::Object>>,
+) -> JsValuePreview {
+    if let Ok(promise) = v8::Local::<v8::Promise>::try_from(value) {
+        let state = match promise.state() {
+            v8::PromiseState::Pending => PromiseState::Pending,
+            v8::PromiseState::Fulfilled => PromiseState::Fulfilled,
+            v8::PromiseState::Rejected => PromiseState::Rejected,
+        };
+        let value = match state {
+            PromiseState::Pending => None,
+            _ => {
+                let result = promise.result(scope);
+                Some(Box::new(preview_value(scope, result, depth + 1, seen)))
+            }
+        };
+        return JsValuePreview::Promise { state, value };
+    }
+
+    if let Ok(array) = v8::Local::<v8::Array>::try_from(value) {
+        let length = array.length() as usize;
+        let items = (0..length.min(MAX_ITEMS))
+            .map(|index| {
+                let item = array
+                    .get_index(scope, index as u32)
+                    .unwrap_or_else(|| v8::undefined(scope).into());
+                preview_value(scope, item, depth + 1, seen)
+            })
+            .collect();
+        return JsValuePreview::Array { items, length };
+    }
+
+    if let Ok(map) = v8::Local::<v8::Map>::try_from(value) {
+        let size = map.size();
+        // as_array는 [key1, value1, key2, value2, ...] 형태
+        let flat = map.as_array(scope);
+        let entries = (0..size.min(MAX_ITEMS))
+            .map(|index| {
+                let key = flat
+                    .get_index(scope, (index * 2) as u32)
+                    .unwrap_or_else(|| v8::undefined(scope).into());
+                let value = flat
+                    .get_index(scope, (index * 2 + 1) as u32)
+                    .unwrap_or_else(|| v8::undefined(scope).into());
+                MapEntryPreview {
+                    key: preview_value(scope, key, depth + 1, seen),
+                    value: preview_value(scope, value, depth + 1, seen),
+                }
+            })
+            .collect();
+        return JsValuePreview::Map { entries, size };
+    }
+
+    if let Ok(set) = v8::Local::<v8::Set>::try_from(value) {
+        let size = set.size();
+        let flat = set.as_array(scope);
+        let items = (0..size.min(MAX_ITEMS))
+            .map(|index| {
+                let item = flat
+                    .get_index(scope, index as u32)
+                    .unwrap_or_else(|| v8::undefined(scope).into());
+                preview_value(scope, item, depth + 1, seen)
+            })
+            .collect();
+        return JsValuePreview::Set { items, size };
+    }
+
+    let keys = object
+        .get_own_property_names(scope, v8::GetPropertyNamesArgs::default())
+        .map(|keys| {
+            (0..keys.length())
+                .filter_map(|index| keys.get_index(scope, index))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let more = keys.len().saturating_sub(MAX_ITEMS);
+    let properties = keys
+        .into_iter()
+        .take(MAX_ITEMS)
+        .map(|key| {
+            let property = object
+                .get(scope, key)
+                .unwrap_or_else(|| v8::undefined(scope).into());
+            PropertyPreview {
+                key: key.to_rust_string_lossy(scope),
+                value: preview_value(scope, property, depth + 1, seen),
+            }
+        })
+        .collect();
+
+    JsValuePreview::Object {
+        class_name,
+        properties,
+        more,
+    }
+}
+
+/// 객체의 문자열 속성 읽기
+fn get_string<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    object: v8::Local<'s, v8::Object>,
+    name: &str,
+) -> Option<String> {
+    let key = v8::String::new(scope, name)?;
+    let value = object.get(scope, key.into())?;
+    if value.is_undefined() {
+        None
+    } else {
+        Some(value.to_rust_string_lossy(scope))
+    }
+}
+
+/// 인자 없는 메서드를 호출하여 문자열 결과 반환 (예외 발생 시 None)
+fn call_method<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    object: v8::Local<'s, v8::Object>,
+    name: &str,
+) -> Option<String> {
+    let scope = &mut v8::TryCatch::new(scope);
+    let key = v8::String::new(scope, name)?;
+    let method = object.get(scope, key.into())?;
+    let method = v8::Local::<v8::Function>::try_from(method).ok()?;
+    let result = method.call(scope, object.into(), &[])?;
+    Some(result.to_rust_string_lossy(scope))
+}